@@ -1,19 +1,37 @@
 use rand::seq::SliceRandom;
 use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashSet;
 
 #[derive(Debug)]
 pub struct CardError {
     pub msg: String,
+    // The offending token and its (0-based) position among the tokens parsed
+    // from the input, when the error came from `cards_from_str`.
+    pub token: Option<String>,
+    pub position: Option<usize>,
 }
 
 impl CardError {
     pub fn new(s: &str) -> CardError {
-        return CardError { msg: s.to_string() };
+        return CardError {
+            msg: s.to_string(),
+            token: None,
+            position: None,
+        };
+    }
+
+    pub fn for_token(token: &str, position: usize) -> CardError {
+        return CardError {
+            msg: format!("Bad card '{}' at position {}", token, position),
+            token: Some(token.to_string()),
+            position: Some(position),
+        };
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Suit {
     Clubs,
     Diamonds,
@@ -59,7 +77,7 @@ const RANK_CHARS: [&'static str; 13] = [
     "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
 ];
 
-#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Hash, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Hash, Copy, Clone, Serialize, Deserialize)]
 pub struct Rank {
     pub value: u32,
 }
@@ -80,7 +98,7 @@ impl Rank {
             "7" => Ok(Rank::num(7)),
             "8" => Ok(Rank::num(8)),
             "9" => Ok(Rank::num(9)),
-            "T" => Ok(Rank::num(10)),
+            "T" | "10" => Ok(Rank::num(10)),
             "J" => Ok(Rank::JACK),
             "Q" => Ok(Rank::QUEEN),
             "K" => Ok(Rank::KING),
@@ -111,14 +129,20 @@ impl Card {
         return Card { rank: r, suit: s };
     }
 
+    // Accepts the canonical two-character form ("QD"), "10" or lowercase as
+    // an alternative to "T" ("10d", "td"), and Unicode suit glyphs ("Q♦").
+    // The suit is always the last character; everything before it is the rank.
     pub fn from(s: &str) -> Result<Card, CardError> {
-        if s.chars().count() == 2 {
-            let mut chars = s.chars();
-            let r = chars.next().unwrap().to_string();
-            let s = chars.next().unwrap().to_string();
-            return Ok(Card::new(Rank::from(&r)?, Suit::from(&s)?));
+        let trimmed = s.trim();
+        let mut chars: Vec<char> = trimmed.chars().collect();
+        if chars.len() < 2 {
+            return Err(CardError::new("Bad string"));
         }
-        return Err(CardError::new("Bad string"));
+        let suit_char = chars.pop().unwrap();
+        let rank_str: String = chars.into_iter().collect();
+        let rank = Rank::from(&rank_str)?;
+        let suit = Suit::from(&suit_char.to_string())?;
+        return Ok(Card::new(rank, suit));
     }
 
     pub fn ascii_string(&self) -> String {
@@ -134,10 +158,35 @@ impl Card {
     }
 }
 
+// Serializes as the compact two-char ascii string ("QS") rather than the
+// derived `{"rank": {"value": 12}, "suit": "spades"}` form, so request/
+// response JSON stays as terse as the hand-rolled parsing in hearts_json.rs.
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&self.ascii_string());
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Card, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        return Card::from(&s).map_err(|e| serde::de::Error::custom(e.msg));
+    }
+}
+
+// Accepts whitespace- and/or comma-separated card tokens; see `Card::from`
+// for the notations tolerated within each token. On a bad token, the
+// returned `CardError` names the token and its (0-based) position so a
+// malformed "hand" field in a JSON request can point the caller at the
+// exact bad card.
 pub fn cards_from_str(s: &str) -> Result<Vec<Card>, CardError> {
     let mut cards: Vec<Card> = Vec::new();
-    for cs in s.split_whitespace() {
-        cards.push(Card::from(&cs)?);
+    let tokens: Vec<&str> = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .collect();
+    for (i, token) in tokens.iter().enumerate() {
+        cards.push(Card::from(token).map_err(|_| CardError::for_token(token, i))?);
     }
     return Ok(cards);
 }
@@ -153,6 +202,66 @@ pub fn symbol_str_from_cards(cards: &[Card]) -> String {
     return s;
 }
 
+pub fn ascii_str_from_cards(cards: &[Card]) -> String {
+    let mut s = String::new();
+    for (i, c) in cards.iter().enumerate() {
+        if i > 0 {
+            s.push_str(" ");
+        }
+        s.push_str(&c.ascii_string());
+    }
+    return s;
+}
+
+// A `#[serde(with = "card::cards_as_str")]` helper for `Vec<Card>` fields that
+// should (de)serialize as a single space-separated ascii string (e.g.
+// `"hand": "KS 9S 2S"`) instead of serde's default JSON array of per-card
+// strings, matching the compact wire format request/response JSON already
+// uses elsewhere.
+pub mod cards_as_str {
+    use super::{ascii_str_from_cards, cards_from_str, Card};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(cards: &Vec<Card>, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&ascii_str_from_cards(cards));
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Card>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        return cards_from_str(&s).map_err(|e| serde::de::Error::custom(e.msg));
+    }
+}
+
+// Like `cards_as_str`, but for a `Vec<Vec<Card>>` field (e.g. one hand per
+// player) that should (de)serialize as an array of space-separated strings.
+pub mod vec_cards_as_str {
+    use super::{ascii_str_from_cards, cards_from_str, Card};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        hands: &Vec<Vec<Card>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(hands.len()))?;
+        for h in hands.iter() {
+            seq.serialize_element(&ascii_str_from_cards(h))?;
+        }
+        return seq.end();
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<Card>>, D::Error> {
+        let strs = Vec::<String>::deserialize(deserializer)?;
+        let mut hands = Vec::with_capacity(strs.len());
+        for s in strs.iter() {
+            hands.push(cards_from_str(s).map_err(|e| serde::de::Error::custom(e.msg))?);
+        }
+        return Ok(hands);
+    }
+}
+
 pub fn for_each_card(mut f: impl FnMut(&Card)) {
     for r in 2..=14 {
         let rank = Rank::num(r);
@@ -163,10 +272,164 @@ pub fn for_each_card(mut f: impl FnMut(&Card)) {
     }
 }
 
+fn card_set_suit_index(suit: Suit) -> u32 {
+    return match suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    };
+}
+
+fn card_set_bit(card: &Card) -> u32 {
+    return (card.rank.value - 2) * 4 + card_set_suit_index(card.suit);
+}
+
+fn card_set_card(bit: u32) -> Card {
+    let suit = match bit % 4 {
+        0 => Suit::Clubs,
+        1 => Suit::Diamonds,
+        2 => Suit::Hearts,
+        _ => Suit::Spades,
+    };
+    return Card::new(Rank::num(bit / 4 + 2), suit);
+}
+
+// A 52-bit set of cards, one bit per card (`(rank - 2) * 4 + suit_index`),
+// for the Monte Carlo hot paths that otherwise build and scan a
+// `HashSet<Card>` per rollout: membership, insert/remove, len, and
+// union/intersection/difference become O(1)/branch-free bit operations.
+// Picking a random member walks the set bits, at most 52 steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub fn new() -> CardSet {
+        return CardSet(0);
+    }
+
+    pub fn from_cards(cards: &[Card]) -> CardSet {
+        let mut set = CardSet::new();
+        for c in cards.iter() {
+            set.insert(c);
+        }
+        return set;
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        return self.0 & (1u64 << card_set_bit(card)) != 0;
+    }
+
+    pub fn insert(&mut self, card: &Card) {
+        self.0 |= 1u64 << card_set_bit(card);
+    }
+
+    pub fn remove(&mut self, card: &Card) {
+        self.0 &= !(1u64 << card_set_bit(card));
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.count_ones() as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    pub fn union(&self, other: &CardSet) -> CardSet {
+        return CardSet(self.0 | other.0);
+    }
+
+    pub fn intersection(&self, other: &CardSet) -> CardSet {
+        return CardSet(self.0 & other.0);
+    }
+
+    pub fn difference(&self, other: &CardSet) -> CardSet {
+        return CardSet(self.0 & !other.0);
+    }
+
+    pub fn to_cards(&self) -> Vec<Card> {
+        return self.iter().collect();
+    }
+
+    pub fn iter(&self) -> CardSetIter {
+        return CardSetIter { bits: self.0 };
+    }
+
+    // Picks a uniformly random member by drawing the k-th set bit for a
+    // `k` drawn from `gen_range(0, len)`, rather than advancing an
+    // iterator over a `HashSet` (as `random_from_set` does).
+    pub fn random_member(&self, mut rng: impl Rng) -> Card {
+        assert!(!self.is_empty());
+        let k = rng.gen_range(0, self.len());
+        return self.iter().nth(k).unwrap();
+    }
+}
+
+pub struct CardSetIter {
+    bits: u64,
+}
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.bits == 0 {
+            return None;
+        }
+        let bit = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        return Some(card_set_card(bit));
+    }
+}
+
+// Serializes as a list of Card's ascii strings, same as a `Vec<Card>`.
+impl Serialize for CardSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return self.to_cards().serialize(serializer);
+    }
+}
+
+impl<'de> Deserialize<'de> for CardSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<CardSet, D::Error> {
+        let cards = Vec::<Card>::deserialize(deserializer)?;
+        return Ok(CardSet::from_cards(&cards));
+    }
+}
+
 pub struct Deck {
     pub cards: Vec<Card>,
 }
 
+// Which cards to strip from a deck so its size divides evenly among
+// `num_players`, e.g. 2♦ for 3 players or 2♦ and 2♣ for 5. Cards are
+// stripped lowest rank first; within a rank, diamonds go before clubs (so
+// 2♣ stays in play as long as possible, since it's what normally opens the
+// first trick), then spades, then hearts last, so point cards go last of
+// all. `hearts::Round::deal` uses this to auto-strip whatever deck it's
+// handed; `Deck::for_players` uses it to build an already-stripped deck.
+pub(crate) fn cards_to_strip(deck: &[Card], num_players: usize) -> Vec<Card> {
+    let strip_suit_order = [Suit::Diamonds, Suit::Clubs, Suit::Spades, Suit::Hearts];
+    let num_to_strip = deck.len() % num_players;
+    let mut stripped: Vec<Card> = Vec::new();
+    for rank_value in 2..=14 {
+        if stripped.len() == num_to_strip {
+            break;
+        }
+        let rank = Rank::num(rank_value);
+        for &suit in strip_suit_order.iter() {
+            if stripped.len() == num_to_strip {
+                break;
+            }
+            let card = Card::new(rank, suit);
+            if deck.contains(&card) {
+                stripped.push(card);
+            }
+        }
+    }
+    return stripped;
+}
+
 impl Deck {
     pub fn new() -> Deck {
         let mut cards: Vec<Card> = Vec::new();
@@ -174,6 +437,21 @@ impl Deck {
         return Deck { cards: cards };
     }
 
+    // The standard Hearts deck for `num_players`: the full 52 cards for 4
+    // players, or with the lowest cards stripped (see `cards_to_strip`:
+    // 2♦ for 3 players, 2♦ 2♣ for 5, 2♦ 2♣ 2♠ 2♥ for 6) so the deck divides
+    // evenly among the players.
+    // Returns the stripped deck together with the cards that were removed,
+    // so callers can record them (e.g. in `RuleSet::removed_cards`) instead
+    // of just losing track of them.
+    pub fn for_players(num_players: usize) -> (Deck, Vec<Card>) {
+        let mut cards: Vec<Card> = Vec::new();
+        for_each_card(|c| cards.push(*c));
+        let removed = cards_to_strip(&cards, num_players);
+        cards.retain(|c| !removed.contains(c));
+        return (Deck { cards: cards }, removed);
+    }
+
     pub fn shuffle(&mut self, mut rng: impl Rng) {
         self.cards.shuffle(&mut rng);
     }
@@ -219,11 +497,11 @@ pub fn random_from_set<T>(items: &HashSet<T>, mut rng: impl Rng) -> &T {
     return ci.next().unwrap();
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CardDistributionPlayerConstraint {
     pub num_cards: usize,
     pub voided_suits: HashSet<Suit>,
-    pub fixed_cards: HashSet<Card>,
+    pub fixed_cards: CardSet,
 }
 
 pub struct CardDistributionRequest {
@@ -233,80 +511,130 @@ pub struct CardDistributionRequest {
     // fixed card that is not in `cards`.
 }
 
+// Tries to extend the bipartite matching `card_to_player` with a card for
+// `player`, via an augmenting path through players whose currently-matched
+// card could instead go elsewhere (Kuhn's algorithm). `visited` prevents
+// revisiting a card within the same augmenting search. It uses `CardSet`
+// rather than `HashSet<Card>` so each membership test and update is a bit
+// operation, since it runs on every candidate card tried while drawing a
+// hand, across 50 hands x 20 rollouts per Monte Carlo decision.
+fn augment_matching(
+    player: usize,
+    legal_cards: &[CardSet],
+    card_to_player: &mut [Option<usize>; 52],
+    visited: &mut CardSet,
+) -> bool {
+    for c in legal_cards[player].iter() {
+        if visited.contains(&c) {
+            continue;
+        }
+        visited.insert(&c);
+        let bit = card_set_bit(&c) as usize;
+        let can_place = match card_to_player[bit] {
+            None => true,
+            Some(bumped_player) => augment_matching(bumped_player, legal_cards, card_to_player, visited),
+        };
+        if can_place {
+            card_to_player[bit] = Some(player);
+            return true;
+        }
+    }
+    return false;
+}
+
+// The size of a maximum bipartite matching between players (each wanting
+// `remaining[i]` more cards, modeled as `remaining[i]` separate slots so
+// flow from a player's source edge can split across cards) and the cards
+// each player could still legally receive. A request for these `remaining`
+// counts is feasible iff this equals `remaining.iter().sum()`: anything
+// less means some cross-player combination of voided suits and fixed
+// cards has left too few legal cards to go around, even though no single
+// player's own legal set is too small.
+fn max_distribution_matching(remaining: &[usize], legal_cards: &[CardSet]) -> usize {
+    let mut card_to_player: [Option<usize>; 52] = [None; 52];
+    let mut flow = 0;
+    for i in 0..remaining.len() {
+        for _ in 0..remaining[i] {
+            let mut visited = CardSet::new();
+            if augment_matching(i, legal_cards, &mut card_to_player, &mut visited) {
+                flow += 1;
+            }
+        }
+    }
+    return flow;
+}
+
+fn distribution_is_feasible(remaining: &[usize], legal_cards: &[CardSet]) -> bool {
+    return max_distribution_matching(remaining, legal_cards) == remaining.iter().sum::<usize>();
+}
+
 fn _possible_card_distribution(
     req: &CardDistributionRequest,
     mut rng: impl Rng,
 ) -> Result<Vec<Vec<Card>>, CardError> {
     let num_players = req.constraints.len();
     let mut result: Vec<Vec<Card>> = Vec::new();
-    let mut legal_cards: Vec<HashSet<Card>> = Vec::new();
+    let mut legal_cards: Vec<CardSet> = Vec::new();
     // Create sets of possible cards for each player.
     for (i, cs) in req.constraints.iter().enumerate() {
-        let mut legal_for_player: HashSet<Card> = HashSet::new();
+        let mut legal_for_player = CardSet::new();
         // Add cards in suits that the player isn't known to be out of.
         for &c in req.cards.iter() {
             if !cs.voided_suits.contains(&c.suit) {
-                legal_for_player.insert(c);
+                legal_for_player.insert(&c);
             }
         }
         // Remove cards that are fixed to other players.
         for (j, other_cs) in req.constraints.iter().enumerate() {
             if i != j {
-                for &c in other_cs.fixed_cards.iter() {
-                    legal_for_player.remove(&c);
-                }
+                legal_for_player = legal_for_player.difference(&other_cs.fixed_cards);
             }
         }
         legal_cards.push(legal_for_player);
         result.push(Vec::new());
     }
-    // Assign cards randomly according to constraints.
-    loop {
-        let mut took_all = false;
+    let mut remaining: Vec<usize> = req.constraints.iter().map(|cs| cs.num_cards).collect();
+    // Check feasibility of the whole request up front via max flow, rather
+    // than discovering a dead end partway through a random assignment. Max
+    // flow catches cross-player infeasibility, not just a single player's
+    // legal set running out, e.g. two players being jointly void in a suit
+    // that forces the third player to hold all of it.
+    if !distribution_is_feasible(&remaining, &legal_cards) {
+        return Err(CardError::new("Cannot satisfy constraints"));
+    }
+    // Assign cards one at a time, round-robin over players that still need
+    // some. Before committing a tentative card, recheck feasibility of the
+    // resulting residual problem; if that choice would make the rest of the
+    // deal impossible, undo it and rule out that card for this player
+    // instead of retrying the whole distribution from scratch.
+    while remaining.iter().sum::<usize>() > 0 {
         for i in 0..num_players {
-            // If any player's remaining cards are forced, take them all.
-            let num_to_fill = req.constraints[i].num_cards - result[i].len();
-            if num_to_fill > 0 {
-                let num_legal = legal_cards[i].len();
-                if num_to_fill > num_legal {
-                    return Err(CardError::new("Cannot satisfy constraints"));
-                }
-                if num_to_fill == num_legal {
-                    let taken_cards = legal_cards[i].clone();
-                    for &c in taken_cards.iter() {
-                        result[i].push(c);
-                    }
-                    for &c in taken_cards.iter() {
-                        for j in 0..num_players {
-                            legal_cards[j].remove(&c);
-                        }
+            if remaining[i] == 0 {
+                continue;
+            }
+            loop {
+                let c = legal_cards[i].random_member(&mut rng);
+                let mut removed_from: Vec<usize> = Vec::new();
+                for j in 0..num_players {
+                    if j != i && legal_cards[j].contains(&c) {
+                        legal_cards[j].remove(&c);
+                        removed_from.push(j);
                     }
-                    took_all = true;
+                }
+                legal_cards[i].remove(&c);
+                remaining[i] -= 1;
+                if distribution_is_feasible(&remaining, &legal_cards) {
+                    result[i].push(c);
                     break;
                 }
-            }
-        }
-        if took_all {
-            continue;
-        }
-        // Nobody had a forced pick, choose one card for one player.
-        let mut chose_card = false;
-        for i in 0..num_players {
-            let num_to_fill = req.constraints[i].num_cards - result[i].len();
-            if num_to_fill > 0 {
-                let c = *random_from_set(&legal_cards[i], &mut rng);
-                result[i].push(c);
-                for j in 0..num_players {
-                    legal_cards[j].remove(&c);
+                // That choice leaves the rest of the deal unsatisfiable;
+                // undo it and don't offer `c` to player `i` again.
+                remaining[i] += 1;
+                for &j in removed_from.iter() {
+                    legal_cards[j].insert(&c);
                 }
-                chose_card = true;
-                break;
             }
         }
-        if !chose_card {
-            // We've have assigned all the cards.
-            break;
-        }
     }
     return Ok(result);
 }
@@ -315,17 +643,12 @@ pub fn possible_card_distribution(
     req: &CardDistributionRequest,
     mut rng: impl Rng,
 ) -> Result<Vec<Vec<Card>>, CardError> {
-    for _ in 0..10000 {
-        let result = _possible_card_distribution(req, &mut rng);
-        if result.is_ok() {
-            return result;
-        }
+    let result = _possible_card_distribution(req, &mut rng);
+    if result.is_err() {
+        println!("cards: {}", all_suit_groups(&req.cards));
+        println!("constraints: {:?}", &req.constraints);
     }
-    println!("cards: {}", all_suit_groups(&req.cards));
-    println!("constraints: {:?}", &req.constraints);
-    return Err(CardError::new(
-        "Cannot satisfy constraints after 10000 attempts",
-    ));
+    return result;
 }
 
 #[cfg(test)]
@@ -381,6 +704,42 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_tolerant_parse() {
+        assert_eq!(
+            Card::from("10H").unwrap(),
+            Card::new(Rank::num(10), Suit::Hearts)
+        );
+        assert_eq!(
+            Card::from("10d").unwrap(),
+            Card::new(Rank::num(10), Suit::Diamonds)
+        );
+        assert_eq!(
+            Card::from(" qs ").unwrap(),
+            Card::new(Rank::QUEEN, Suit::Spades)
+        );
+    }
+
+    #[test]
+    fn test_parse_comma_and_mixed_separators() {
+        let actual = cards_from_str("2c, 10D ,AS\tQ♥, KS").unwrap();
+        let expected = vec![
+            Card::new(Rank::num(2), Suit::Clubs),
+            Card::new(Rank::num(10), Suit::Diamonds),
+            Card::new(Rank::ACE, Suit::Spades),
+            Card::new(Rank::QUEEN, Suit::Hearts),
+            Card::new(Rank::KING, Suit::Spades),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_error_names_bad_token_and_position() {
+        let err = cards_from_str("2C 8D ZZ QD").unwrap_err();
+        assert_eq!(err.token, Some("ZZ".to_string()));
+        assert_eq!(err.position, Some(2));
+    }
+
     #[test]
     fn test_card() {
         let c1 = Card::new(Rank::num(3), Suit::Clubs);
@@ -397,6 +756,32 @@ mod test {
         assert_eq!(c4.symbol_string(), "Q♣");
     }
 
+    #[test]
+    fn test_card_serde_compact_ascii_string() {
+        let card = Card::new(Rank::QUEEN, Suit::Spades);
+        let json = serde_json::to_string(&card).unwrap();
+        assert_eq!(json, "\"QS\"");
+        let round_tripped: Card = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, card);
+        assert!(serde_json::from_str::<Card>("\"ZZ\"").is_err());
+    }
+
+    #[test]
+    fn test_card_distribution_player_constraint_serde_round_trip() {
+        let mut constraint = CardDistributionPlayerConstraint {
+            num_cards: 3,
+            voided_suits: HashSet::new(),
+            fixed_cards: CardSet::new(),
+        };
+        constraint.voided_suits.insert(Suit::Hearts);
+        constraint.fixed_cards.insert(&c("QS"));
+        let json = serde_json::to_string(&constraint).unwrap();
+        let round_tripped: CardDistributionPlayerConstraint = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped.num_cards, 3);
+        assert!(round_tripped.voided_suits.contains(&Suit::Hearts));
+        assert!(round_tripped.fixed_cards.contains(&c("QS")));
+    }
+
     #[test]
     fn test_hand_suits() {
         let c1 = Card::new(Rank::num(7), Suit::Hearts);
@@ -413,13 +798,85 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_card_set_insert_contains_remove() {
+        let mut set = CardSet::new();
+        assert!(set.is_empty());
+        set.insert(&c("QS"));
+        set.insert(&c("2C"));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&c("QS")));
+        assert!(!set.contains(&c("KS")));
+        set.remove(&c("QS"));
+        assert!(!set.contains(&c("QS")));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_card_set_from_cards_and_iter_round_trip() {
+        let hand = cv("AS KS 2H 5D");
+        let set = CardSet::from_cards(&hand);
+        assert_eq!(set.len(), 4);
+        let round_tripped = set.to_cards();
+        assert_eq!(round_tripped.len(), hand.len());
+        for card in hand.iter() {
+            assert!(round_tripped.contains(card));
+        }
+    }
+
+    #[test]
+    fn test_card_set_union_intersection_difference() {
+        let a = CardSet::from_cards(&cv("AS KS QS"));
+        let b = CardSet::from_cards(&cv("QS JS"));
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(a.intersection(&b).to_cards(), cv("QS"));
+        assert_eq!(a.difference(&b).len(), 2);
+        assert!(!a.difference(&b).contains(&c("QS")));
+    }
+
+    #[test]
+    fn test_card_set_random_member_is_a_member() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(99);
+        let set = CardSet::from_cards(&cv("2C 7D QH AS"));
+        for _ in 0..20 {
+            assert!(set.contains(&set.random_member(&mut rng)));
+        }
+    }
+
+    #[test]
+    fn test_deck_for_players_divides_evenly() {
+        for &num_players in [3, 4, 5, 6].iter() {
+            let (deck, removed) = Deck::for_players(num_players);
+            assert_eq!(deck.cards.len() + removed.len(), 52);
+            assert_eq!(deck.cards.len() % num_players, 0);
+            for card in removed.iter() {
+                assert!(!deck.cards.contains(card));
+            }
+        }
+    }
+
+    #[test]
+    fn test_deck_for_players_strips_low_diamonds_and_clubs_first() {
+        let (_, removed) = Deck::for_players(3);
+        assert_eq!(removed, cv("2D"));
+        let (_, removed) = Deck::for_players(5);
+        assert_eq!(removed, cv("2D 2C"));
+    }
+
+    #[test]
+    fn test_deck_for_players_four_keeps_full_deck() {
+        let (deck, removed) = Deck::for_players(4);
+        assert_eq!(deck.cards.len(), 52);
+        assert!(removed.is_empty());
+    }
+
     fn make_constraints(n: usize, num_cards: usize) -> Vec<CardDistributionPlayerConstraint> {
         let mut c: Vec<CardDistributionPlayerConstraint> = Vec::new();
         for i in 0..n {
             c.push(CardDistributionPlayerConstraint {
                 num_cards: num_cards,
                 voided_suits: HashSet::new(),
-                fixed_cards: HashSet::new(),
+                fixed_cards: CardSet::new(),
             });
         }
         return c;
@@ -470,10 +927,10 @@ mod test {
         let mut rng: StdRng = SeedableRng::seed_from_u64(42);
         let cards = cv("2C 2D 2H 2S 3C 3D 3H 3S 4C 4D 4H 4S");
         let mut constraints = make_constraints(4, 3);
-        constraints[1].fixed_cards.insert(c("2H"));
-        constraints[3].fixed_cards.insert(c("3D"));
-        constraints[3].fixed_cards.insert(c("4D"));
-        constraints[3].fixed_cards.insert(c("AD"));
+        constraints[1].fixed_cards.insert(&c("2H"));
+        constraints[3].fixed_cards.insert(&c("3D"));
+        constraints[3].fixed_cards.insert(&c("4D"));
+        constraints[3].fixed_cards.insert(&c("AD"));
         let req = CardDistributionRequest {
             cards: cards,
             constraints: constraints,
@@ -490,7 +947,6 @@ mod test {
     }
 
     #[test]
-    #[ignore]
     fn test_card_distribution_combination() {
         let mut rng: StdRng = SeedableRng::seed_from_u64(42);
         let cards = cv("AS KS QS JS TS 9S AH KH QH");
@@ -502,11 +958,60 @@ mod test {
             constraints: constraints,
         };
         // Players 1 and 2 have no hearts, so they must have all the spades
-        // between them, so player 0 can't have spades. Unfortunately the
-        // algorithm can't determine this yet.
+        // between them, which forces player 0 to have all three hearts.
+        // The bipartite feasibility check sees this cross-player
+        // implication even though neither player's own legal set is too
+        // small on its own.
         let dist = _possible_card_distribution(&req, &mut rng).unwrap();
         assert!(dist[0].contains(&c("AH")));
         assert!(dist[0].contains(&c("KH")));
         assert!(dist[0].contains(&c("QH")));
     }
+
+    #[test]
+    fn test_card_distribution_interlocking_voids_infeasible() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        // Only 2 clubs are in play, but players 0 and 1 are both void in
+        // every other suit, so between them they need 4 clubs: impossible.
+        let cards = cv("2C 3C 2D 3D 2H 3H 2S 3S");
+        let mut constraints = make_constraints(4, 2);
+        for i in 0..2 {
+            constraints[i].voided_suits.insert(Suit::Diamonds);
+            constraints[i].voided_suits.insert(Suit::Hearts);
+            constraints[i].voided_suits.insert(Suit::Spades);
+        }
+        let req = CardDistributionRequest {
+            cards: cards,
+            constraints: constraints,
+        };
+        assert!(_possible_card_distribution(&req, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_card_distribution_interlocking_voids_feasible() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(7);
+        // Players 0 and 1 are both void in diamonds and spades, so between
+        // them they must hold all 4 clubs and hearts; that's exactly
+        // enough for their 4 combined cards, forcing players 2 and 3 to
+        // split the diamonds and spades.
+        let cards = cv("2C 3C 2H 3H 2D 3D 2S 3S");
+        let mut constraints = make_constraints(4, 2);
+        for i in 0..2 {
+            constraints[i].voided_suits.insert(Suit::Diamonds);
+            constraints[i].voided_suits.insert(Suit::Spades);
+        }
+        let req = CardDistributionRequest {
+            cards: cards,
+            constraints: constraints,
+        };
+        let dist = _possible_card_distribution(&req, &mut rng).unwrap();
+        for i in 0..2 {
+            assert_eq!(ranks_for_suit(&dist[i], Suit::Diamonds).len(), 0);
+            assert_eq!(ranks_for_suit(&dist[i], Suit::Spades).len(), 0);
+        }
+        for i in 2..4 {
+            assert_eq!(ranks_for_suit(&dist[i], Suit::Clubs).len(), 0);
+            assert_eq!(ranks_for_suit(&dist[i], Suit::Hearts).len(), 0);
+        }
+    }
 }
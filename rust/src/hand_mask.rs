@@ -0,0 +1,213 @@
+use crate::card::*;
+
+// Bit layout: 13 bits per suit (rank 2 at the low end, ace at the high end),
+// in the same Clubs/Diamonds/Hearts/Spades order as `Suit`'s declaration.
+const BITS_PER_SUIT: u32 = 13;
+
+pub const CLUBS: u64 = 0x1FFF;
+pub const DIAMONDS: u64 = CLUBS << BITS_PER_SUIT;
+pub const HEARTS: u64 = CLUBS << (2 * BITS_PER_SUIT);
+pub const SPADES: u64 = CLUBS << (3 * BITS_PER_SUIT);
+
+fn suit_offset(suit: Suit) -> u32 {
+    return match suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => BITS_PER_SUIT,
+        Suit::Hearts => 2 * BITS_PER_SUIT,
+        Suit::Spades => 3 * BITS_PER_SUIT,
+    };
+}
+
+fn suit_mask(suit: Suit) -> u64 {
+    return match suit {
+        Suit::Clubs => CLUBS,
+        Suit::Diamonds => DIAMONDS,
+        Suit::Hearts => HEARTS,
+        Suit::Spades => SPADES,
+    };
+}
+
+fn bit_index(card: &Card) -> u32 {
+    return suit_offset(card.suit) + (card.rank.value - 2);
+}
+
+// Returns the mask of every card of `rank`, one bit per suit.
+pub fn rank_mask(rank: Rank) -> u64 {
+    let bit = rank.value - 2;
+    return (1u64 << bit) | (1u64 << (bit + BITS_PER_SUIT)) | (1u64 << (bit + 2 * BITS_PER_SUIT))
+        | (1u64 << (bit + 3 * BITS_PER_SUIT));
+}
+
+// A 52-bit representation of a set of cards, one bit per card, for the
+// suit/rank queries the passing and play heuristics do most often (how many
+// cards of a suit are held, is a specific card held, what's the highest/
+// lowest card in a suit). These are all O(1) bit operations instead of
+// scanning a `Vec<Card>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HandMask(pub u64);
+
+impl HandMask {
+    pub fn new() -> HandMask {
+        return HandMask(0);
+    }
+
+    pub fn from_cards(cards: &[Card]) -> HandMask {
+        let mut mask = HandMask::new();
+        for c in cards.iter() {
+            mask.insert(c);
+        }
+        return mask;
+    }
+
+    pub fn to_cards(&self) -> Vec<Card> {
+        let mut cards = Vec::new();
+        for_each_card(|c| {
+            if self.contains(c) {
+                cards.push(*c);
+            }
+        });
+        return cards;
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        return (self.0 & (1u64 << bit_index(card))) != 0;
+    }
+
+    pub fn insert(&mut self, card: &Card) {
+        self.0 |= 1u64 << bit_index(card);
+    }
+
+    pub fn remove(&mut self, card: &Card) {
+        self.0 &= !(1u64 << bit_index(card));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    pub fn count_in_suit(&self, suit: Suit) -> u32 {
+        return (self.0 & suit_mask(suit)).count_ones();
+    }
+
+    // The subset of this hand in `suit`, as a mask, for callers (e.g. a
+    // follow-suit check) that need the full set of matching cards rather
+    // than just a count or an endpoint.
+    pub fn in_suit(&self, suit: Suit) -> HandMask {
+        return HandMask(self.0 & suit_mask(suit));
+    }
+
+    pub fn highest_in_suit(&self, suit: Suit) -> Option<Card> {
+        let bits = self.0 & suit_mask(suit);
+        if bits == 0 {
+            return None;
+        }
+        let highest_bit = 63 - bits.leading_zeros();
+        let rank_value = (highest_bit - suit_offset(suit)) + 2;
+        return Some(Card::new(Rank::num(rank_value), suit));
+    }
+
+    pub fn lowest_in_suit(&self, suit: Suit) -> Option<Card> {
+        let bits = self.0 & suit_mask(suit);
+        if bits == 0 {
+            return None;
+        }
+        let lowest_bit = bits.trailing_zeros();
+        let rank_value = (lowest_bit - suit_offset(suit)) + 2;
+        return Some(Card::new(Rank::num(rank_value), suit));
+    }
+
+    // Ranks held in `suit`, highest first. Unlike the other queries this
+    // isn't O(1), but it's only needed by heuristics that want the full
+    // ordering (e.g. the second-lowest card in a suit) rather than a count or
+    // an endpoint.
+    pub fn ranks_in_suit(&self, suit: Suit) -> Vec<Rank> {
+        let mut ranks = Vec::new();
+        for v in (2..=14).rev() {
+            if self.contains(&Card::new(Rank::num(v), suit)) {
+                ranks.push(Rank::num(v));
+            }
+        }
+        return ranks;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn c(s: &str) -> Vec<Card> {
+        cards_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_from_cards_and_contains() {
+        let mask = HandMask::from_cards(&c("AS QS 4C 2H"));
+        assert!(mask.contains(&Card::from("AS").unwrap()));
+        assert!(mask.contains(&Card::from("2H").unwrap()));
+        assert!(!mask.contains(&Card::from("KS").unwrap()));
+    }
+
+    #[test]
+    fn test_to_cards_round_trip() {
+        let hand = c("AS QS 4C 2H TD");
+        let mask = HandMask::from_cards(&hand);
+        let mut round_tripped = mask.to_cards();
+        let mut expected = hand.clone();
+        round_tripped.sort_by_key(|c| (c.suit as u8, c.rank.value));
+        expected.sort_by_key(|c| (c.suit as u8, c.rank.value));
+        assert_eq!(round_tripped, expected);
+    }
+
+    #[test]
+    fn test_count_in_suit() {
+        let mask = HandMask::from_cards(&c("AS QS 4C 2H 9H"));
+        assert_eq!(mask.count_in_suit(Suit::Spades), 2);
+        assert_eq!(mask.count_in_suit(Suit::Hearts), 2);
+        assert_eq!(mask.count_in_suit(Suit::Clubs), 1);
+        assert_eq!(mask.count_in_suit(Suit::Diamonds), 0);
+    }
+
+    #[test]
+    fn test_highest_and_lowest_in_suit() {
+        let mask = HandMask::from_cards(&c("AS QS 4S 2H 9H"));
+        assert_eq!(mask.highest_in_suit(Suit::Spades), Some(Card::from("AS").unwrap()));
+        assert_eq!(mask.lowest_in_suit(Suit::Spades), Some(Card::from("4S").unwrap()));
+        assert_eq!(mask.highest_in_suit(Suit::Diamonds), None);
+    }
+
+    #[test]
+    fn test_ranks_in_suit_descending() {
+        let mask = HandMask::from_cards(&c("AS QS 4S 9H"));
+        assert_eq!(
+            mask.ranks_in_suit(Suit::Spades),
+            vec![Rank::ACE, Rank::QUEEN, Rank::num(4)]
+        );
+    }
+
+    #[test]
+    fn test_in_suit() {
+        let mask = HandMask::from_cards(&c("AS QS 4C 2H"));
+        assert_eq!(mask.in_suit(Suit::Spades), HandMask::from_cards(&c("AS QS")));
+        assert_eq!(mask.in_suit(Suit::Diamonds), HandMask::new());
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut mask = HandMask::from_cards(&c("AS QS"));
+        let four_spades = Card::from("4S").unwrap();
+        assert!(!mask.contains(&four_spades));
+        mask.insert(&four_spades);
+        assert!(mask.contains(&four_spades));
+        mask.remove(&four_spades);
+        assert!(!mask.contains(&four_spades));
+    }
+
+    #[test]
+    fn test_rank_mask_covers_all_suits() {
+        let mask = rank_mask(Rank::QUEEN);
+        for suit in [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades] {
+            let hand = HandMask(mask);
+            assert!(hand.contains(&Card::new(Rank::QUEEN, suit)));
+        }
+    }
+}
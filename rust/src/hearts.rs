@@ -1,5 +1,6 @@
 use crate::card::*;
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashSet;
 
 pub const QUEEN_OF_SPADES: Card = Card {
@@ -32,22 +33,186 @@ impl Player {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MoonShooting {
-    DISABLED,
-    OPPONENTS_PLUS_26,
-    // TODO: Allow option of subtracting 26 from the shooter's score.
+    Disabled,
+    #[serde(rename = "opponents_plus_26")]
+    OpponentsPlus26,
+    #[serde(rename = "self_minus_26")]
+    SelfMinus26,
+    // The shooter picks whichever of the above is better for them. There's no
+    // interactive hook for that choice here, so we model it as the shooter
+    // playing optimally: whichever outcome leaves them with the lower score.
+    ShooterChooses,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+// One entry in a `RuleSet::pass_direction_schedule`: which way cards move
+// before a round, or `Hold` for a no-pass hand. Resolved to the numeric
+// `pass_direction` a `Round`/`Match` actually uses (how many seats past the
+// passer, in increasing seat order, the cards land) relative to `num_players`, since
+// "across" and "right" only make sense relative to the table size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassDirectionKind {
+    Left,
+    Right,
+    Across,
+    Hold,
+}
+
+impl PassDirectionKind {
+    pub fn pass_direction(&self, num_players: usize) -> u32 {
+        return match self {
+            PassDirectionKind::Hold => 0,
+            PassDirectionKind::Left => 1,
+            PassDirectionKind::Right => (num_players - 1) as u32,
+            PassDirectionKind::Across => (num_players / 2) as u32,
+        };
+    }
+}
+
+// Space-separated "CARD=VALUE" pairs (e.g. "TD=10 QS=0"), keeping
+// `point_overrides` as terse in JSON as the rest of `RuleSet`.
+mod point_overrides_as_str {
+    use crate::card::{Card, CardError};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    fn to_str(overrides: &[(Card, i32)]) -> String {
+        let mut s = String::new();
+        for (i, (card, value)) in overrides.iter().enumerate() {
+            if i > 0 {
+                s.push_str(" ");
+            }
+            s.push_str(&format!("{}={}", card.ascii_string(), value));
+        }
+        return s;
+    }
+
+    fn from_str(s: &str) -> Result<Vec<(Card, i32)>, CardError> {
+        let mut overrides: Vec<(Card, i32)> = Vec::new();
+        for (i, token) in s.split_whitespace().enumerate() {
+            let (card_str, value_str) = token
+                .split_once('=')
+                .ok_or_else(|| CardError::for_token(token, i))?;
+            let card = Card::from(card_str).map_err(|_| CardError::for_token(token, i))?;
+            let value: i32 = value_str
+                .parse()
+                .map_err(|_| CardError::for_token(token, i))?;
+            overrides.push((card, value));
+        }
+        return Ok(overrides);
+    }
+
+    pub fn serialize<S: Serializer>(
+        overrides: &Vec<(Card, i32)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&to_str(overrides));
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Card, i32)>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        return from_str(&s).map_err(|e| serde::de::Error::custom(e.msg));
+    }
+}
+
+// Space-separated pass-direction tokens ("left", "right", "across", "hold"),
+// keeping `pass_direction_schedule` as terse in JSON as the rest of
+// `RuleSet`.
+mod pass_direction_schedule_as_str {
+    use super::PassDirectionKind;
+    use crate::card::CardError;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    fn to_str(schedule: &[PassDirectionKind]) -> String {
+        let mut s = String::new();
+        for (i, kind) in schedule.iter().enumerate() {
+            if i > 0 {
+                s.push_str(" ");
+            }
+            s.push_str(match kind {
+                PassDirectionKind::Left => "left",
+                PassDirectionKind::Right => "right",
+                PassDirectionKind::Across => "across",
+                PassDirectionKind::Hold => "hold",
+            });
+        }
+        return s;
+    }
+
+    fn from_str(s: &str) -> Result<Vec<PassDirectionKind>, CardError> {
+        let mut schedule = Vec::new();
+        for (i, token) in s.split_whitespace().enumerate() {
+            let kind = match token {
+                "left" => PassDirectionKind::Left,
+                "right" => PassDirectionKind::Right,
+                "across" => PassDirectionKind::Across,
+                "hold" => PassDirectionKind::Hold,
+                _ => return Err(CardError::for_token(token, i)),
+            };
+            schedule.push(kind);
+        }
+        return Ok(schedule);
+    }
+
+    pub fn serialize<S: Serializer>(
+        schedule: &Vec<PassDirectionKind>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&to_str(schedule));
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<PassDirectionKind>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        return from_str(&s).map_err(|e| serde::de::Error::custom(e.msg));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RuleSet {
+    #[serde(default = "RuleSet::default_num_players")]
     pub num_players: usize,
+    #[serde(default, with = "crate::card::cards_as_str")]
     pub removed_cards: Vec<Card>,
+    #[serde(default = "RuleSet::default_point_limit")]
     pub point_limit: u32,
+    #[serde(default)]
     pub points_on_first_trick: bool,
+    #[serde(default)]
     pub queen_breaks_hearts: bool,
+    #[serde(default)]
     pub jd_minus_10: bool,
+    // Each heart worth its own rank (2-14) instead of a flat `heart_value`,
+    // as in the "spot hearts" variant. Checked before `heart_value` for the
+    // same reason `point_overrides` is checked before either: the more
+    // specific rule should win.
+    #[serde(default)]
+    pub spot_hearts: bool,
+    #[serde(default = "RuleSet::default_moon_shooting")]
     pub moon_shooting: MoonShooting,
+    #[serde(default = "RuleSet::default_heart_value")]
+    pub heart_value: i32,
+    #[serde(default = "RuleSet::default_queen_of_spades_value")]
+    pub queen_of_spades_value: i32,
+    // Point values for specific cards that override the rules above, for
+    // variants with their own bonus/penalty cards (e.g. a "new moon" ten of
+    // diamonds). Checked before `spot_hearts`/`heart_value`/
+    // `queen_of_spades_value`/`jd_minus_10`, so an override always wins.
+    #[serde(default, with = "point_overrides_as_str")]
+    pub point_overrides: Vec<(Card, i32)>,
+    // The pass direction for round 1, round 2, etc., cycling once exhausted
+    // (the classic cycle is left/right/across/hold, repeating every 4
+    // rounds). `PassDirectionKind::Hold` gives a no-pass hand.
+    #[serde(
+        default = "RuleSet::default_pass_direction_schedule",
+        with = "pass_direction_schedule_as_str"
+    )]
+    pub pass_direction_schedule: Vec<PassDirectionKind>,
 }
 
 impl RuleSet {
@@ -57,6 +222,23 @@ impl RuleSet {
     pub fn default_point_limit() -> u32 {
         100
     }
+    pub fn default_moon_shooting() -> MoonShooting {
+        MoonShooting::OpponentsPlus26
+    }
+    pub fn default_heart_value() -> i32 {
+        1
+    }
+    pub fn default_queen_of_spades_value() -> i32 {
+        13
+    }
+    pub fn default_pass_direction_schedule() -> Vec<PassDirectionKind> {
+        return vec![
+            PassDirectionKind::Left,
+            PassDirectionKind::Right,
+            PassDirectionKind::Across,
+            PassDirectionKind::Hold,
+        ];
+    }
 }
 
 impl Default for RuleSet {
@@ -68,16 +250,26 @@ impl Default for RuleSet {
             points_on_first_trick: false,
             queen_breaks_hearts: false,
             jd_minus_10: false,
-            moon_shooting: MoonShooting::OPPONENTS_PLUS_26,
+            spot_hearts: false,
+            moon_shooting: MoonShooting::OpponentsPlus26,
+            heart_value: RuleSet::default_heart_value(),
+            queen_of_spades_value: RuleSet::default_queen_of_spades_value(),
+            point_overrides: Vec::new(),
+            pass_direction_schedule: RuleSet::default_pass_direction_schedule(),
         };
     }
 }
 
 pub fn points_for_card(c: &Card, rules: &RuleSet) -> i32 {
+    for &(override_card, value) in rules.point_overrides.iter() {
+        if override_card == *c {
+            return value;
+        }
+    }
     if c.suit == Suit::Hearts {
-        return 1;
+        return if rules.spot_hearts { c.rank.value as i32 } else { rules.heart_value };
     } else if *c == QUEEN_OF_SPADES {
-        return 13;
+        return rules.queen_of_spades_value;
     } else if rules.jd_minus_10 && *c == JACK_OF_DIAMONDS {
         return -10;
     }
@@ -93,50 +285,117 @@ pub fn points_for_cards(cards: &[Card], rules: &RuleSet) -> i32 {
 }
 
 // This takes shooting the moon into account. If you don't want that, set
-// rules.moon_shooting to `MoonShooting::DISABLED`.
+// rules.moon_shooting to `MoonShooting::Disabled`.
 pub fn points_for_tricks(tricks: &[Trick], rules: &RuleSet) -> Vec<i32> {
     let mut points: Vec<i32> = Vec::new();
     points.resize(rules.num_players, 0);
     for t in tricks.iter() {
         points[t.winner as usize] += points_for_cards(&t.cards, rules);
     }
-    if rules.moon_shooting != MoonShooting::DISABLED {
+    if rules.moon_shooting != MoonShooting::Disabled {
         if let Some(shooter) = moon_shooter(tricks, &points, rules) {
-            for p in 0..rules.num_players {
-                points[p] += if (p == shooter) { -26 } else { 26 };
-            }
+            apply_moon_shot(&mut points, shooter, rules);
         }
     }
     return points;
 }
 
-// Returns the index of the player who has taken all hearts and the queen of spades.
+fn opponents_plus_26(points: &[i32], shooter: usize, max_penalty: i32) -> Vec<i32> {
+    return points
+        .iter()
+        .enumerate()
+        .map(|(p, &pts)| if p == shooter { pts - max_penalty } else { pts + max_penalty })
+        .collect();
+}
+
+fn self_minus_26(points: &[i32], shooter: usize, max_penalty: i32) -> Vec<i32> {
+    return points
+        .iter()
+        .enumerate()
+        .map(|(p, &pts)| if p == shooter { pts - 2 * max_penalty } else { pts })
+        .collect();
+}
+
+fn apply_moon_shot(points: &mut Vec<i32>, shooter: usize, rules: &RuleSet) {
+    let max_penalty = max_achievable_penalty(rules);
+    *points = match rules.moon_shooting {
+        MoonShooting::Disabled => points.clone(),
+        MoonShooting::OpponentsPlus26 => opponents_plus_26(points, shooter, max_penalty),
+        MoonShooting::SelfMinus26 => self_minus_26(points, shooter, max_penalty),
+        MoonShooting::ShooterChooses => {
+            let plus_26 = opponents_plus_26(points, shooter, max_penalty);
+            let minus_26 = self_minus_26(points, shooter, max_penalty);
+            if minus_26[shooter] <= plus_26[shooter] {
+                minus_26
+            } else {
+                plus_26
+            }
+        }
+    };
+}
+
+// The total penalty points available in the active deck (after
+// `removed_cards`), i.e. what a player has to sweep entirely to shoot the
+// moon. Computed from the live scoring config rather than assumed to be 26,
+// so this stays correct for custom `heart_value`/`queen_of_spades_value` and
+// `point_overrides`.
+fn max_achievable_penalty(rules: &RuleSet) -> i32 {
+    let mut total = 0;
+    for_each_card(|c| {
+        if !rules.removed_cards.contains(c) {
+            let value = points_for_card(c, rules);
+            if value > 0 {
+                total += value;
+            }
+        }
+    });
+    return total;
+}
+
+// Returns the index of the player who has taken every penalty card in the
+// active deck (the default game: all hearts and the queen of spades).
 fn moon_shooter(tricks: &[Trick], points: &[i32], rules: &RuleSet) -> Option<usize> {
-    fn find_shooter(pts: &[i32]) -> Option<usize> {
+    let max_penalty = max_achievable_penalty(rules);
+    if max_penalty <= 0 {
+        return None;
+    }
+
+    fn find_shooter(pts: &[i32], max_penalty: i32) -> Option<usize> {
         for p in 0..pts.len() {
-            if pts[p] == 26 {
+            if pts[p] == max_penalty {
                 return Some(p);
             }
         }
         return None;
     }
 
-    if rules.jd_minus_10 {
-        // Undo the -10 points for JD. We have to do this rather than just
-        // looking at the point totals because [16, 0, 0, 0] may or may not be
-        // a shoot, depending on whether one of the players with zero took
-        // ten hearts along with the jack of diamonds and also ten hearts.
-        let mut points_without_jd = points.to_vec();
-        for t in tricks.iter() {
-            if t.cards.contains(&JACK_OF_DIAMONDS) {
-                points_without_jd[t.winner as usize] += 10;
-                break;
+    // Undo any negative point values (jd_minus_10, or a negative
+    // point_override) before comparing against `max_penalty`. We have to do
+    // this rather than just looking at the point totals because [16, 0, 0, 0]
+    // may or may not be a shoot, depending on whether one of the players with
+    // zero took a negative-valued card along with all the penalty cards.
+    let mut adjusted_points = points.to_vec();
+    for t in tricks.iter() {
+        for &c in t.cards.iter() {
+            let value = points_for_card(&c, rules);
+            if value < 0 {
+                adjusted_points[t.winner as usize] -= value;
             }
         }
-        return find_shooter(&points_without_jd);
-    } else {
-        return find_shooter(points);
     }
+    return find_shooter(&adjusted_points, max_penalty);
+}
+
+// Like the shooter detection inside `points_for_tricks`, but usable by
+// callers (e.g. a simulation harness) that only have the finished tricks and
+// not the pre-adjustment point totals `points_for_tricks` computes internally.
+pub fn moon_shooter_for_tricks(tricks: &[Trick], rules: &RuleSet) -> Option<usize> {
+    let mut raw_points: Vec<i32> = Vec::new();
+    raw_points.resize(rules.num_players, 0);
+    for t in tricks.iter() {
+        raw_points[t.winner as usize] += points_for_cards(&t.cards, rules);
+    }
+    return moon_shooter(tricks, &raw_points, rules);
 }
 
 pub fn highest_in_trick(cards: &[Card]) -> &Card {
@@ -155,9 +414,54 @@ pub struct Trick {
     pub winner: usize,
 }
 
-#[derive(Debug, Clone)]
+// `winner` is serialized so a completed trick round-trips exactly, but is
+// optional on the way in and computed from `leader`/`cards` (the same way
+// `Round::play_card` derives it) when the caller hasn't already figured it
+// out themselves.
+#[derive(Serialize, Deserialize)]
+struct TrickWire {
+    leader: usize,
+    #[serde(with = "crate::card::cards_as_str")]
+    cards: Vec<Card>,
+    #[serde(default)]
+    winner: Option<usize>,
+}
+
+impl Serialize for Trick {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return TrickWire {
+            leader: self.leader,
+            cards: self.cards.clone(),
+            winner: Some(self.winner),
+        }
+        .serialize(serializer);
+    }
+}
+
+impl<'de> Deserialize<'de> for Trick {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Trick, D::Error> {
+        let wire = TrickWire::deserialize(deserializer)?;
+        let winner = match wire.winner {
+            Some(w) => w,
+            None => {
+                if wire.cards.is_empty() {
+                    return Err(serde::de::Error::custom("a completed trick has no cards"));
+                }
+                (wire.leader + trick_winner_index(&wire.cards)) % wire.cards.len()
+            }
+        };
+        return Ok(Trick {
+            leader: wire.leader,
+            cards: wire.cards,
+            winner: winner,
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrickInProgress {
     pub leader: usize,
+    #[serde(default, with = "crate::card::cards_as_str")]
     pub cards: Vec<Card>,
 }
 
@@ -170,15 +474,37 @@ impl TrickInProgress {
     }
 }
 
-fn find_card(players: &[Player], target: &Card) -> usize {
+fn find_card_opt(players: &[Player], target: &Card) -> Option<usize> {
     for (i, p) in players.iter().enumerate() {
-        for c in p.hand.iter() {
-            if c == target {
-                return i;
+        if p.hand.contains(target) {
+            return Some(i);
+        }
+    }
+    return None;
+}
+
+// Finds the player who should lead the first trick: whoever holds 2♣, or
+// if that card was stripped out of the deck (e.g. for a 3-player game),
+// whoever holds the lowest club, or failing that whoever holds the single
+// lowest-ranked card in play.
+pub(crate) fn find_opening_leader(players: &[Player]) -> usize {
+    if let Some(p) = find_card_opt(players, &TWO_OF_CLUBS) {
+        return p;
+    }
+    for rank in 2..=14 {
+        if let Some(p) = find_card_opt(players, &Card::new(Rank::num(rank), Suit::Clubs)) {
+            return p;
+        }
+    }
+    let mut best: Option<(usize, Card)> = None;
+    for (i, p) in players.iter().enumerate() {
+        for &c in p.hand.iter() {
+            if best.is_none() || c.rank < best.unwrap().1.rank {
+                best = Some((i, c));
             }
         }
     }
-    panic!("Didn't find {}", target.symbol_string());
+    return best.expect("No cards dealt").0;
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -203,14 +529,23 @@ pub struct Round {
 
 impl Round {
     pub fn deal(deck: &Deck, rules: &RuleSet, scores: &[i32], pass_direction: u32) -> Round {
+        let mut remaining: Vec<Card> = deck
+            .cards
+            .iter()
+            .filter(|c| !rules.removed_cards.contains(c))
+            .cloned()
+            .collect();
+        let auto_stripped = cards_to_strip(&remaining, rules.num_players);
+        remaining.retain(|c| !auto_stripped.contains(c));
+
         let mut players: Vec<Player> = Vec::new();
-        // TODO: Don't hardcode to 4 players and 13 cards.
-        for i in 0..4 {
-            let start = 13 * i;
-            let end = 13 * (i + 1);
-            players.push(Player::new(&deck.cards[start..end]));
+        let cards_per_player = remaining.len() / rules.num_players;
+        for i in 0..rules.num_players {
+            let start = cards_per_player * i;
+            let end = cards_per_player * (i + 1);
+            players.push(Player::new(&remaining[start..end]));
         }
-        let current_player_index = find_card(&players, &TWO_OF_CLUBS);
+        let current_player_index = find_opening_leader(&players);
         let status = if pass_direction == 0 {
             RoundStatus::Playing
         } else {
@@ -300,7 +635,7 @@ impl Round {
             p.hand = new_hand;
             assert_eq!(p.hand.len(), n);
         }
-        self.current_trick.leader = find_card(&self.players, &TWO_OF_CLUBS);
+        self.current_trick.leader = find_opening_leader(&self.players);
         self.status = RoundStatus::Playing;
     }
 
@@ -340,6 +675,98 @@ impl Round {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchPhase {
+    AwaitingPass,
+    Playing,
+    RoundComplete,
+    MatchComplete,
+}
+
+// Walks `rules.pass_direction_schedule`, cycling once it's exhausted.
+// "Across" assumes an even number of players.
+fn pass_direction_for_round(round_num: u32, rules: &RuleSet) -> u32 {
+    let schedule = &rules.pass_direction_schedule;
+    let kind = schedule[((round_num - 1) as usize) % schedule.len()];
+    return kind.pass_direction(rules.num_players);
+}
+
+// Drives a sequence of Rounds to a final score, rotating the pass direction
+// each round and stopping once a player reaches `rules.point_limit`.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub rules: RuleSet,
+    pub scores: Vec<i32>,
+    pub round_num: u32,
+    pub round: Round,
+    pub phase: MatchPhase,
+}
+
+impl Match {
+    pub fn deal_first_round(rules: &RuleSet, deck: &Deck) -> Match {
+        let scores: Vec<i32> = vec![0; rules.num_players];
+        return Match::deal_round(rules, deck, scores, 1);
+    }
+
+    fn deal_round(rules: &RuleSet, deck: &Deck, scores: Vec<i32>, round_num: u32) -> Match {
+        let pass_direction = pass_direction_for_round(round_num, rules);
+        let round = Round::deal(deck, rules, &scores, pass_direction);
+        let phase = if pass_direction == 0 {
+            MatchPhase::Playing
+        } else {
+            MatchPhase::AwaitingPass
+        };
+        return Match {
+            rules: rules.clone(),
+            scores: scores,
+            round_num: round_num,
+            round: round,
+            phase: phase,
+        };
+    }
+
+    pub fn pass_cards(&mut self) {
+        assert!(self.phase == MatchPhase::AwaitingPass);
+        self.round.pass_cards();
+        self.phase = MatchPhase::Playing;
+    }
+
+    pub fn play_card(&mut self, card: &Card) -> Result<(), ()> {
+        assert!(self.phase == MatchPhase::Playing);
+        self.round.play_card(card)?;
+        if self.round.is_over() {
+            let round_points = self.round.points_taken();
+            for p in 0..self.rules.num_players {
+                self.scores[p] += round_points[p];
+            }
+            self.phase = if self.is_over() {
+                MatchPhase::MatchComplete
+            } else {
+                MatchPhase::RoundComplete
+            };
+        }
+        return Ok(());
+    }
+
+    pub fn is_over(&self) -> bool {
+        return self.scores.iter().any(|&s| s >= (self.rules.point_limit as i32));
+    }
+
+    // Players with the lowest cumulative score; more than one entry means a tie.
+    pub fn winners(&self) -> Vec<usize> {
+        let min = *self.scores.iter().min().unwrap();
+        return (0..self.scores.len()).filter(|&p| self.scores[p] == min).collect();
+    }
+
+    pub fn start_next_round(&mut self, deck: &Deck) {
+        assert!(self.phase == MatchPhase::RoundComplete);
+        let rules = self.rules.clone();
+        let scores = self.scores.clone();
+        let round_num = self.round_num + 1;
+        *self = Match::deal_round(&rules, deck, scores, round_num);
+    }
+}
+
 fn are_hearts_broken(
     current_trick: &TrickInProgress,
     prev_tricks: &[Trick],
@@ -595,4 +1022,243 @@ mod test {
         rules.jd_minus_10 = true;
         assert_eq!(points_for_tricks(&tricks, &rules), vec![26, -10, 26, 26]);
     }
+
+    #[test]
+    fn test_shooting_self_minus_26() {
+        let mut rules = RuleSet::default();
+        rules.moon_shooting = MoonShooting::SelfMinus26;
+        let tricks = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "AD QS JD JH", 1),
+            make_trick(1, "AH 2H 3H 4H", 1),
+            make_trick(1, "KH 5H 6H 7H", 1),
+            make_trick(1, "QH 8H 9H TH", 1),
+        ];
+        assert_eq!(points_for_tricks(&tricks, &rules), vec![0, -26, 0, 0]);
+    }
+
+    #[test]
+    fn test_shooting_chooses_whichever_is_lower_for_shooter() {
+        let tricks = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "AD QS JD JH", 1),
+            make_trick(1, "AH 2H 3H 4H", 1),
+            make_trick(1, "KH 5H 6H 7H", 1),
+            make_trick(1, "QH 8H 9H TH", 1),
+        ];
+        let mut rules = RuleSet::default();
+        rules.moon_shooting = MoonShooting::ShooterChooses;
+        // Shooter starts at 0 points, so -26 beats +26 for everyone else.
+        assert_eq!(points_for_tricks(&tricks, &rules), vec![0, -26, 0, 0]);
+    }
+
+    #[test]
+    fn test_custom_card_point_values() {
+        let mut rules = RuleSet::default();
+        rules.heart_value = 2;
+        rules.queen_of_spades_value = 20;
+        let tricks = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "3D 6D QS 5D", 2),
+            make_trick(2, "4D JD AH KD", 1),
+        ];
+        assert_eq!(points_for_tricks(&tricks, &rules), vec![0, 2, 20, 0]);
+    }
+
+    #[test]
+    fn test_moon_shooter_for_tricks() {
+        let rules = RuleSet::default();
+        let shot_moon = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "AD QS JD JH", 1),
+            make_trick(1, "AH 2H 3H 4H", 1),
+            make_trick(1, "KH 5H 6H 7H", 1),
+            make_trick(1, "QH 8H 9H TH", 1),
+        ];
+        assert_eq!(moon_shooter_for_tricks(&shot_moon, &rules), Some(1));
+
+        let no_shot = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "3D 6D QS 5D", 2),
+            make_trick(2, "4D JD AH KD", 1),
+        ];
+        assert_eq!(moon_shooter_for_tricks(&no_shot, &rules), None);
+    }
+
+    #[test]
+    fn test_moon_shooter_detection_follows_custom_point_values() {
+        let mut rules = RuleSet::default();
+        rules.heart_value = 2;
+        rules.queen_of_spades_value = 20;
+        let shot_moon = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "AD QS JD JH", 1),
+            make_trick(1, "AH 2H 3H 4H", 1),
+            make_trick(1, "KH 5H 6H 7H", 1),
+            make_trick(1, "QH 8H 9H TH", 1),
+        ];
+        // With heart_value doubled and QS worth 20, the moon is 46 points, not
+        // the default 26, so detection has to track the live scoring config.
+        assert_eq!(points_for_tricks(&shot_moon, &rules), vec![46, 0, 46, 46]);
+        assert_eq!(moon_shooter_for_tricks(&shot_moon, &rules), Some(1));
+    }
+
+    #[test]
+    fn test_point_overrides_take_priority_and_feed_moon_detection() {
+        let mut rules = RuleSet::default();
+        rules.point_overrides = vec![(Card::from("TH").unwrap(), 10)];
+        let tricks = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "AD QS JD JH", 1),
+            make_trick(1, "AH 2H 3H 4H", 1),
+            make_trick(1, "KH 5H 6H 7H", 1),
+            make_trick(1, "QH 8H 9H TH", 1),
+        ];
+        // TH is normally a plain heart (1 point); the override bumps it to 10,
+        // so the moon is now 35 points, and that's what a sweep scores.
+        assert_eq!(points_for_tricks(&tricks, &rules), vec![35, 0, 35, 35]);
+        assert_eq!(moon_shooter_for_tricks(&tricks, &rules), Some(1));
+
+        let no_shot = vec![
+            make_trick(0, "2C AC KC QC", 1),
+            make_trick(1, "3D 6D QS 5D", 2),
+            make_trick(2, "4D JD AH KD", 1),
+        ];
+        assert_eq!(moon_shooter_for_tricks(&no_shot, &rules), None);
+    }
+
+    #[test]
+    fn test_cards_to_strip_divides_deck_evenly() {
+        let deck = Deck::new();
+        assert_eq!(cards_to_strip(&deck.cards, 4), Vec::new());
+        assert_eq!(cards_to_strip(&deck.cards, 3), c("2D"));
+        assert_eq!(cards_to_strip(&deck.cards, 5), c("2D 2C"));
+        assert_eq!(cards_to_strip(&deck.cards, 6), c("2D 2C 2S 2H"));
+    }
+
+    #[test]
+    fn test_deal_generalizes_to_other_player_counts() {
+        let deck = Deck::new();
+        for &num_players in [3, 5, 6].iter() {
+            let mut rules = RuleSet::default();
+            rules.num_players = num_players;
+            let scores = vec![0; num_players];
+            let round = Round::deal(&deck, &rules, &scores, 0);
+            assert_eq!(round.players.len(), num_players);
+            let total_dealt: usize = round.players.iter().map(|p| p.hand.len()).sum();
+            assert_eq!(total_dealt % num_players, 0);
+            for p in round.players.iter() {
+                assert_eq!(p.hand.len(), total_dealt / num_players);
+            }
+            // Whoever leads must actually hold the card they're leading with.
+            let leader = round.current_trick.leader;
+            assert!(round.players[leader].hand.contains(&TWO_OF_CLUBS)
+                || !round.players.iter().any(|p| p.hand.contains(&TWO_OF_CLUBS)));
+        }
+    }
+
+    #[test]
+    fn test_opening_leader_falls_back_when_2c_is_stripped() {
+        let players = vec![
+            Player::new(&c("3C 4D")),
+            Player::new(&c("5D 6D")),
+        ];
+        assert_eq!(find_opening_leader(&players), 0);
+    }
+
+    #[test]
+    fn test_pass_direction_cycle() {
+        let rules = RuleSet::default();
+        assert_eq!(pass_direction_for_round(1, &rules), 1);
+        assert_eq!(pass_direction_for_round(2, &rules), 3);
+        assert_eq!(pass_direction_for_round(3, &rules), 2);
+        assert_eq!(pass_direction_for_round(4, &rules), 0);
+        assert_eq!(pass_direction_for_round(5, &rules), 1);
+    }
+
+    #[test]
+    fn test_pass_direction_schedule_is_configurable() {
+        // A variant with no hold hands, just left/right/across.
+        let mut rules = RuleSet::default();
+        rules.pass_direction_schedule = vec![
+            PassDirectionKind::Left,
+            PassDirectionKind::Right,
+            PassDirectionKind::Across,
+        ];
+        assert_eq!(pass_direction_for_round(1, &rules), 1);
+        assert_eq!(pass_direction_for_round(2, &rules), 3);
+        assert_eq!(pass_direction_for_round(3, &rules), 2);
+        assert_eq!(pass_direction_for_round(4, &rules), 1);
+    }
+
+    #[test]
+    fn test_spot_hearts_scores_rank_value() {
+        let mut rules = RuleSet::default();
+        rules.spot_hearts = true;
+        assert_eq!(points_for_card(&Card::from("2H").unwrap(), &rules), 2);
+        assert_eq!(points_for_card(&Card::from("TH").unwrap(), &rules), 10);
+        assert_eq!(points_for_card(&Card::from("AH").unwrap(), &rules), 14);
+        // Non-hearts and point_overrides are unaffected.
+        assert_eq!(points_for_card(&QUEEN_OF_SPADES, &rules), 13);
+    }
+
+    fn pass_first_three_cards(m: &mut Match) {
+        for p in 0..m.rules.num_players {
+            let cards = m.round.players[p].hand[0..3].to_vec();
+            m.round.set_passed_cards_for_player(p, &cards);
+        }
+        m.pass_cards();
+    }
+
+    #[test]
+    fn test_match_continues_to_next_round_with_rotated_pass_direction() {
+        let rules = RuleSet::default();
+        let deck = Deck::new();
+        let mut m = Match::deal_first_round(&rules, &deck);
+        assert_eq!(m.phase, MatchPhase::AwaitingPass);
+        assert_eq!(m.round.pass_direction, 1);
+
+        pass_first_three_cards(&mut m);
+        assert_eq!(m.phase, MatchPhase::Playing);
+        while !m.round.is_over() {
+            let card = m.round.legal_plays()[0];
+            m.play_card(&card).unwrap();
+        }
+        // A single round can take at most 26 points, well under the default
+        // 100 point limit, so the match isn't over yet.
+        assert_eq!(m.phase, MatchPhase::RoundComplete);
+
+        m.start_next_round(&deck);
+        assert_eq!(m.round_num, 2);
+        assert_eq!(m.phase, MatchPhase::AwaitingPass);
+        assert_eq!(m.round.pass_direction, 3);
+    }
+
+    #[test]
+    fn test_match_complete_reports_winners() {
+        let mut rules = RuleSet::default();
+        rules.point_limit = 1;
+        let deck = Deck::new();
+        let mut m = Match::deal_first_round(&rules, &deck);
+
+        pass_first_three_cards(&mut m);
+        while m.phase == MatchPhase::Playing {
+            let card = m.round.legal_plays()[0];
+            m.play_card(&card).unwrap();
+        }
+        // Every round awards at least 26 points in total, so someone always reaches
+        // a limit of 1 after the first round.
+        assert_eq!(m.phase, MatchPhase::MatchComplete);
+        let winners = m.winners();
+        assert!(!winners.is_empty());
+        let min_score = m.scores[winners[0]];
+        for &w in winners.iter() {
+            assert_eq!(m.scores[w], min_score);
+        }
+        for (p, &s) in m.scores.iter().enumerate() {
+            if !winners.contains(&p) {
+                assert!(s > min_score);
+            }
+        }
+    }
 }
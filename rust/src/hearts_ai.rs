@@ -1,8 +1,12 @@
 use crate::card::*;
+use crate::hand_mask::HandMask;
 use crate::hearts;
 
 use rand::seq::SliceRandom;
 use rand::Rng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -12,13 +16,111 @@ pub struct MonteCarloParams {
     pub rollouts_per_hand: i32,
 }
 
-pub enum CardToPlayStrategy {
-    Random,
-    AvoidPoints,
-    MixedRandomAvoidPoints(f64),
-    MonteCarloRandom(MonteCarloParams),
-    MonteCarloAvoidPoints(MonteCarloParams),
-    MonteCarloMixedRandomAvoidPoints(f64, MonteCarloParams),
+// Parameters for `DeterminizedUctChooser`. `num_hands` determinizations are
+// sampled as in `MonteCarloParams`, but instead of flat random rollouts,
+// each one grows its own UCT search tree of `iterations_per_hand`
+// simulations before being discarded. `exploration_constant` is the `c` in
+// the UCB1 formula `W/N + c*sqrt(ln(N_parent)/N_child)`; higher values favor
+// exploring less-visited actions over exploiting the best one seen so far.
+#[derive(Debug, Copy, Clone)]
+pub struct UctParams {
+    pub num_hands: i32,
+    pub iterations_per_hand: i32,
+    pub exploration_constant: f64,
+}
+
+// Extension point for card-choice policies (flat heuristics, Monte Carlo
+// rollouts, or a caller's own implementation), replacing the old closed
+// CardToPlayStrategy enum so new choosers can be registered (see
+// `chooser_by_name`) and mixed per seat (e.g. `Tournament::strategies`)
+// without editing this file. Takes `&dyn` rather than generic parameters so
+// choosers can be boxed and stored together in a `Vec<Box<dyn CardChooser>>`.
+pub trait CardChooser: Any {
+    fn choose_card(&self, req: &dyn ChooseCardToPlayRequest, rng: &mut dyn RngCore) -> Card;
+
+    // Like `choose_card`, but for callers that also want to show their work:
+    // a per-card evaluation score (when the chooser computes one) and how
+    // many rollouts were run (0 for choosers that don't sample). Defaults to
+    // reporting no evaluations; Monte Carlo-based choosers override this.
+    fn choose_card_with_evaluation(
+        &self,
+        req: &dyn ChooseCardToPlayRequest,
+        rng: &mut dyn RngCore,
+    ) -> CardChoice {
+        return CardChoice {
+            card: self.choose_card(req, rng),
+            evaluations: Vec::new(),
+            rollouts: 0,
+        };
+    }
+
+    // Lets callers (e.g. tests) downcast a `Box<dyn CardChooser>` back to its
+    // concrete type to inspect its parameters.
+    fn as_any(&self) -> &dyn Any;
+}
+
+pub struct RandomChooser;
+
+pub struct AvoidPointsChooser;
+
+pub struct MixedRandomAvoidPointsChooser {
+    pub p_random: f64,
+}
+
+// Runs Monte Carlo rollouts (see `choose_card_monte_carlo_detailed`), using
+// `rollout_chooser` to play out each hypothetical hand to completion.
+pub struct MonteCarloChooser {
+    pub params: MonteCarloParams,
+    pub rollout_chooser: Box<dyn CardChooser>,
+}
+
+// Runs determinized UCT search (see `choose_card_determinized_uct_detailed`):
+// unlike `MonteCarloChooser`'s flat rollouts, each determinization grows a
+// UCT tree that concentrates further simulations on promising lines, using
+// `rollout_chooser` only once a simulation falls off the tree's expanded
+// nodes.
+pub struct DeterminizedUctChooser {
+    pub params: UctParams,
+    pub rollout_chooser: Box<dyn CardChooser>,
+}
+
+// Resolves one of the built-in choosers by name, for FFI entry points that
+// let a caller select AI strategy/strength via a JSON descriptor (see
+// hearts_json::parse_strategy) instead of recompiling with a hardcoded
+// chooser. Returns None for an unrecognized name.
+pub fn chooser_by_name(
+    name: &str,
+    mix_ratio: f64,
+    mc_params: MonteCarloParams,
+) -> Option<Box<dyn CardChooser>> {
+    return match name {
+        "random" => Some(Box::new(RandomChooser)),
+        "avoid_points" => Some(Box::new(AvoidPointsChooser)),
+        "mixed_random_avoid_points" => {
+            Some(Box::new(MixedRandomAvoidPointsChooser { p_random: mix_ratio }))
+        }
+        "monte_carlo_random" => Some(Box::new(MonteCarloChooser {
+            params: mc_params,
+            rollout_chooser: Box::new(RandomChooser),
+        })),
+        "monte_carlo_avoid_points" => Some(Box::new(MonteCarloChooser {
+            params: mc_params,
+            rollout_chooser: Box::new(AvoidPointsChooser),
+        })),
+        "monte_carlo_mixed_random_avoid_points" => Some(Box::new(MonteCarloChooser {
+            params: mc_params,
+            rollout_chooser: Box::new(MixedRandomAvoidPointsChooser { p_random: mix_ratio }),
+        })),
+        "determinized_uct" => Some(Box::new(DeterminizedUctChooser {
+            params: UctParams {
+                num_hands: mc_params.num_hands,
+                iterations_per_hand: mc_params.rollouts_per_hand,
+                exploration_constant: DEFAULT_UCT_EXPLORATION_CONSTANT,
+            },
+            rollout_chooser: Box::new(AvoidPointsChooser),
+        })),
+        _ => None,
+    };
 }
 
 // Interface for the inputs used to choose a card to play. CardToPlayDirectRequest is a struct
@@ -38,14 +140,19 @@ pub trait ChooseCardToPlayRequest {
     fn legal_plays(&self) -> Vec<Card>;
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct CardToPlayDirectRequest {
+    #[serde(default)]
     pub rules: hearts::RuleSet,
     pub scores_before_round: Vec<i32>,
+    #[serde(with = "crate::card::cards_as_str")]
     pub hand: Vec<Card>,
     pub prev_tricks: Vec<hearts::Trick>,
     pub current_trick: hearts::TrickInProgress,
     pub pass_direction: u32,
+    #[serde(with = "crate::card::cards_as_str")]
     pub passed_cards: Vec<Card>,
+    #[serde(with = "crate::card::cards_as_str")]
     pub received_cards: Vec<Card>,
 }
 
@@ -91,9 +198,12 @@ impl ChooseCardToPlayRequest for hearts::Round {
     fn legal_plays(&self) -> Vec<Card> {self.legal_plays()}
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct CardsToPassRequest {
+    #[serde(default)]
     pub rules: hearts::RuleSet,
     pub scores_before_round: Vec<i32>,
+    #[serde(with = "crate::card::cards_as_str")]
     pub hand: Vec<Card>,
     pub direction: u32,
     pub num_cards: u32,
@@ -125,141 +235,311 @@ pub fn choose_cards_to_pass_random(req: &CardsToPassRequest) -> Vec<Card> {
     return req.hand[0..(req.num_cards as usize)].to_vec();
 }
 
-fn danger_for_card(card: &Card, ranks: &[Rank], req: &CardsToPassRequest) -> i32 {
-    assert!(ranks.len() > 0);
-    let cval = card.rank.value as i32;
-    let lowest_rank_in_suit = ranks[ranks.len() - 1].value as i32;
-    match card.suit {
-        Suit::Spades => {
-            if card.rank < Rank::QUEEN {
-                return 0;
-            }
-            // Assuming 4 or more spades are safe, probably not true.
-            if ranks.len() >= 4 {
-                return 0;
-            }
-            // Always pass QS.
-            if card.rank == Rank::QUEEN {
-                return 100;
-            }
-            // If we're passing the queen right, it's ok to keep AS and KS
-            // because we'll be able to safely play them (as long as we
-            // have a lower spade).
-            let passing_right = ((req.direction as usize) == req.rules.num_players - 1);
-            let has_queen = ranks.contains(&Rank::QUEEN);
-            let has_low_spade = (ranks[ranks.len() - 1] < Rank::QUEEN);
-            return if passing_right && has_queen && has_low_spade {
-                cval - 5
-            } else {
-                100
-            };
-        }
-        Suit::Hearts => {
-            return cval + lowest_rank_in_suit;
-        }
-        Suit::Diamonds => {
-            return cval + lowest_rank_in_suit;
-        }
-        Suit::Clubs => {
-            // 2C is "higher" than AC for purposes of passing.
-            // TODO: We probably want to pass AC less often because winning
-            // the first trick can be helpful and doesn't risk points.
-            let adj_rank = (if cval == 2 { 14 } else { cval - 1 });
-            if lowest_rank_in_suit == 2 {
-                // Probably pass singleton 2C.
-                if ranks.len() == 1 {
-                    return 50;
-                }
-                let second_lowest_club = ranks[ranks.len() - 2].value as i32;
-                return adj_rank + second_lowest_club;
-            } else {
-                return adj_rank + lowest_rank_in_suit - 1;
-            }
-        }
+const ALL_SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+// Suits of 4 or more cards are assumed to be safe to hold: there are enough
+// low cards in the suit to duck under anything dangerous the rest of the
+// round throws at it.
+const SAFE_SUIT_LENGTH: u32 = 4;
+
+// A suit's contribution to `evaluate_hand`'s danger assessment: how long the
+// suit is, how many of its cards are "unprotected" high cards (queen or
+// above, in a suit too short to duck under them), and a rank-weighted risk
+// score for the suit that's 0 once the suit is long enough to be safe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuitAssessment {
+    pub length: u32,
+    pub unprotected_high_cards: u32,
+    pub risk: f64,
+}
+
+// A hand's shape summarized for the purposes of choosing which cards are
+// dangerous to hold, in the spirit of a poker hand evaluator turning a set
+// of cards into a scored rank rather than a pile of ad-hoc rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandAssessment {
+    pub by_suit: HashMap<Suit, SuitAssessment>,
+    // Rough estimate of the chance of eventually being stuck taking the
+    // queen of spades, from holding it in a short, unprotected suit.
+    pub eat_queen_prob: f64,
+    // Rough estimate of the chance of eventually being stuck taking a heart.
+    pub eat_heart_prob: f64,
+    // Suits short enough (a singleton or doubleton) that passing away the
+    // rest of them would leave us void.
+    pub voidable_suits: Vec<Suit>,
+}
+
+// The risk contributed by holding `card` in its suit: its rank value,
+// except the jack of diamonds is harmless under `jd_minus_10` since we'd
+// rather keep and win with it than pass it away.
+fn suit_card_risk_value(card: &Card, rules: &hearts::RuleSet) -> f64 {
+    if card.suit == Suit::Diamonds && card.rank == Rank::JACK && rules.jd_minus_10 {
+        return 0.0;
     }
+    return card.rank.value as f64;
 }
 
-pub fn choose_cards_to_pass(req: &CardsToPassRequest) -> Vec<Card> {
-    let mut suit_ranks: HashMap<Suit, Vec<Rank>> = HashMap::new();
-    for suit in vec![Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-        suit_ranks.insert(suit, ranks_for_suit(&req.hand, suit));
-    }
-    let mut card_danger: HashMap<Card, i32> = HashMap::new();
-    for &c in req.hand.iter() {
-        card_danger.insert(
-            c,
-            danger_for_card(&c, suit_ranks.get(&c.suit).unwrap(), req),
+pub fn evaluate_hand(hand: &[Card], rules: &hearts::RuleSet) -> HandAssessment {
+    let mask = HandMask::from_cards(hand);
+    let mut by_suit = HashMap::new();
+    for &suit in ALL_SUITS.iter() {
+        let length = mask.count_in_suit(suit);
+        let is_safe = length >= SAFE_SUIT_LENGTH;
+        let unprotected_high_cards = if is_safe {
+            0
+        } else {
+            mask.ranks_in_suit(suit)
+                .iter()
+                .filter(|&&r| r >= Rank::QUEEN)
+                .count() as u32
+        };
+        let risk = if is_safe {
+            0.0
+        } else {
+            mask.ranks_in_suit(suit)
+                .iter()
+                .map(|&r| suit_card_risk_value(&Card::new(r, suit), rules))
+                .sum()
+        };
+        by_suit.insert(
+            suit,
+            SuitAssessment {
+                length,
+                unprotected_high_cards,
+                risk,
+            },
         );
     }
-    let mut sorted_cards: Vec<Card> = req.hand.clone();
-    sorted_cards.sort_by_key(|c| -card_danger.get(c).unwrap());
-    return sorted_cards[0..(req.num_cards as usize)].to_vec();
-}
 
-fn is_nonrecursive(strategy: &CardToPlayStrategy) -> bool {
-    return match strategy {
-        CardToPlayStrategy::Random => true,
-        CardToPlayStrategy::AvoidPoints => true,
-        CardToPlayStrategy::MixedRandomAvoidPoints(_) => true,
-        _ => false,
+    let spades_length = mask.count_in_suit(Suit::Spades);
+    let eat_queen_prob = if mask.contains(&hearts::QUEEN_OF_SPADES) && spades_length < SAFE_SUIT_LENGTH {
+        1.0 / (spades_length as f64)
+    } else {
+        0.0
+    };
+    let hearts_assessment = &by_suit[&Suit::Hearts];
+    let eat_heart_prob = if hearts_assessment.length == 0 {
+        0.0
+    } else {
+        hearts_assessment.risk / ((hearts_assessment.length as f64) * (Rank::ACE.value as f64))
+    };
+    let voidable_suits = ALL_SUITS
+        .iter()
+        .copied()
+        .filter(|&s| {
+            let n = mask.count_in_suit(s);
+            n >= 1 && n <= 2
+        })
+        .collect();
+
+    return HandAssessment {
+        by_suit,
+        eat_queen_prob,
+        eat_heart_prob,
+        voidable_suits,
     };
 }
 
-fn choose_card_nonrecursive(
-    req: &impl ChooseCardToPlayRequest,
-    strategy: &CardToPlayStrategy,
-    mut rng: impl Rng,
-) -> Card {
-    return match strategy {
-        CardToPlayStrategy::Random => choose_card_random(req, rng),
-        CardToPlayStrategy::AvoidPoints => choose_card_avoid_points(req, rng),
-        CardToPlayStrategy::MixedRandomAvoidPoints(p_random) => {
-            if rng.gen_range(0.0_f64..1.0_f64) < *p_random {
-                choose_card_random(req, rng)
-            } else {
-                choose_card_avoid_points(req, rng)
-            }
+impl HandAssessment {
+    // Higher means more dangerous to be holding this hand. Spades danger is
+    // dominated by unprotected high spades (any queen, king or ace in a short
+    // suit) and the chance of eating the queen; hearts and diamonds scale with the rank-weighted risk of being
+    // stuck taking tricks in a short suit, with hearts scaled up further
+    // under `spot_hearts`.
+    pub fn danger_score(&self, rules: &hearts::RuleSet) -> f64 {
+        let mut score = 0.0;
+        score += (self.by_suit[&Suit::Spades].unprotected_high_cards as f64) * 100.0;
+        score += self.eat_queen_prob * 50.0;
+        let heart_point_value = if rules.spot_hearts { 2.0 } else { 1.0 };
+        score += self.by_suit[&Suit::Hearts].risk * heart_point_value;
+        score += self.by_suit[&Suit::Diamonds].risk;
+        return score;
+    }
+}
+
+// Combinations of `k` indices chosen from `0..n`, in ascending lexicographic
+// order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut current = Vec::new();
+    fn helper(start: usize, n: usize, k: usize, current: &mut Vec<usize>, result: &mut Vec<Vec<usize>>) {
+        if current.len() == k {
+            result.push(current.clone());
+            return;
         }
-        _ => panic!("Invalid strategy"),
-    };
+        for i in start..n {
+            current.push(i);
+            helper(i + 1, n, k, current, result);
+            current.pop();
+        }
+    }
+    helper(0, n, k, &mut current, &mut result);
+    return result;
 }
 
-pub fn choose_card(
-    req: &impl ChooseCardToPlayRequest,
-    strategy: &CardToPlayStrategy,
-    mut rng: impl Rng,
-) -> Card {
-    if is_nonrecursive(strategy) {
-        return choose_card_nonrecursive(req, strategy, &mut rng);
+// With the queen of spades passed to the right along with a low spade to
+// duck under future leads, the ace and king of spades are safe to keep
+// rather than unprotected, since we won't be the one stuck with the queen.
+fn passing_right_protects_spades(req: &CardsToPassRequest) -> bool {
+    let passing_right = (req.direction as usize) == req.rules.num_players - 1;
+    if !passing_right {
+        return false;
+    }
+    let mask = HandMask::from_cards(&req.hand);
+    if !mask.contains(&hearts::QUEEN_OF_SPADES) {
+        return false;
     }
-    match strategy {
-        CardToPlayStrategy::MonteCarloRandom(mc_params) => {
-            choose_card_monte_carlo(req, *mc_params, &CardToPlayStrategy::Random, &mut rng)
+    return mask
+        .lowest_in_suit(Suit::Spades)
+        .map_or(false, |c| c.rank < Rank::QUEEN);
+}
+
+// The danger score of the hand that would remain after passing away
+// `discarded`, plus the `passing_right_protects_spades` adjustment.
+fn remaining_hand_danger(req: &CardsToPassRequest, discarded: &[Card]) -> f64 {
+    let remaining: Vec<Card> = req
+        .hand
+        .iter()
+        .filter(|c| !discarded.contains(*c))
+        .copied()
+        .collect();
+    let mut score = evaluate_hand(&remaining, &req.rules).danger_score(&req.rules);
+    if passing_right_protects_spades(req) {
+        let remaining_mask = HandMask::from_cards(&remaining);
+        for rank in [Rank::ACE, Rank::KING] {
+            if remaining_mask.contains(&Card::new(rank, Suit::Spades)) {
+                score -= 100.0;
+            }
         }
+    }
+    return score;
+}
 
-        CardToPlayStrategy::MonteCarloAvoidPoints(mc_params) => {
-            choose_card_monte_carlo(req, *mc_params, &CardToPlayStrategy::AvoidPoints, &mut rng)
+// Picks the `req.num_cards` to discard whose passing minimizes the danger
+// score of the resulting hand (ties broken by discarding the least total
+// card value, so equally safe passes don't waste strategically useful
+// cards), rather than following fixed per-card rules.
+fn best_cards_to_pass(req: &CardsToPassRequest) -> Vec<Card> {
+    let num_cards = req.num_cards as usize;
+    let mut best_indices: Vec<usize> = (0..num_cards).collect();
+    let mut best_score = f64::INFINITY;
+    let mut best_discarded_value = i32::MAX;
+    for indices in combinations(req.hand.len(), num_cards) {
+        let discarded: Vec<Card> = indices.iter().map(|&i| req.hand[i]).collect();
+        let score = remaining_hand_danger(req, &discarded);
+        let discarded_value: i32 = discarded.iter().map(|c| c.rank.value as i32).sum();
+        if score < best_score - 1e-9
+            || ((score - best_score).abs() < 1e-9 && discarded_value < best_discarded_value)
+        {
+            best_score = score;
+            best_discarded_value = discarded_value;
+            best_indices = indices;
         }
+    }
+    return best_indices.iter().map(|&i| req.hand[i]).collect();
+}
 
-        CardToPlayStrategy::MonteCarloMixedRandomAvoidPoints(p_rand, mc_params) => {
-            choose_card_monte_carlo(
-                req,
-                *mc_params,
-                &CardToPlayStrategy::MixedRandomAvoidPoints(*p_rand),
-                &mut rng,
-            )
+pub fn choose_cards_to_pass(req: &CardsToPassRequest) -> Vec<Card> {
+    return best_cards_to_pass(req);
+}
+
+// Like `choose_cards_to_pass`, but also returns a danger score for every
+// card in hand (the reduction in the hand's danger score from passing it
+// alone), highest-danger first, for callers that want to show their work
+// (e.g. the JSON response format).
+pub fn choose_cards_to_pass_with_scores(req: &CardsToPassRequest) -> (Vec<Card>, Vec<(Card, i32)>) {
+    let full_score = evaluate_hand(&req.hand, &req.rules).danger_score(&req.rules);
+    let mut scored: Vec<(Card, i32)> = req
+        .hand
+        .iter()
+        .map(|&c| {
+            let marginal = full_score - remaining_hand_danger(req, &[c]);
+            (c, marginal.round() as i32)
+        })
+        .collect();
+    scored.sort_by_key(|&(_, d)| -d);
+    let chosen = choose_cards_to_pass(req);
+    return (chosen, scored);
+}
+
+impl CardChooser for RandomChooser {
+    fn choose_card(&self, req: &dyn ChooseCardToPlayRequest, rng: &mut dyn RngCore) -> Card {
+        return choose_card_random(req, rng);
+    }
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+impl CardChooser for AvoidPointsChooser {
+    fn choose_card(&self, req: &dyn ChooseCardToPlayRequest, rng: &mut dyn RngCore) -> Card {
+        return choose_card_avoid_points(req, rng);
+    }
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+impl CardChooser for MixedRandomAvoidPointsChooser {
+    fn choose_card(&self, req: &dyn ChooseCardToPlayRequest, rng: &mut dyn RngCore) -> Card {
+        if rng.gen_range(0.0_f64..1.0_f64) < self.p_random {
+            return choose_card_random(req, rng);
         }
+        return choose_card_avoid_points(req, rng);
+    }
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
+
+impl CardChooser for MonteCarloChooser {
+    fn choose_card(&self, req: &dyn ChooseCardToPlayRequest, rng: &mut dyn RngCore) -> Card {
+        return self.choose_card_with_evaluation(req, rng).card;
+    }
+    fn choose_card_with_evaluation(
+        &self,
+        req: &dyn ChooseCardToPlayRequest,
+        rng: &mut dyn RngCore,
+    ) -> CardChoice {
+        return choose_card_monte_carlo_detailed(req, self.params, self.rollout_chooser.as_ref(), rng);
+    }
+    fn as_any(&self) -> &dyn Any {
+        return self;
+    }
+}
 
-        _ => panic!("Unknown strategy"),
+impl CardChooser for DeterminizedUctChooser {
+    fn choose_card(&self, req: &dyn ChooseCardToPlayRequest, rng: &mut dyn RngCore) -> Card {
+        return self.choose_card_with_evaluation(req, rng).card;
+    }
+    fn choose_card_with_evaluation(
+        &self,
+        req: &dyn ChooseCardToPlayRequest,
+        rng: &mut dyn RngCore,
+    ) -> CardChoice {
+        return choose_card_determinized_uct_detailed(req, self.params, self.rollout_chooser.as_ref(), rng);
+    }
+    fn as_any(&self) -> &dyn Any {
+        return self;
     }
 }
 
-pub fn choose_card_random(req: &impl ChooseCardToPlayRequest, mut rng: impl Rng) -> Card {
+// Thin free-function wrappers for callers that don't want to name the trait
+// method directly (mirrors the old enum-based `choose_card`).
+pub fn choose_card(
+    req: &impl ChooseCardToPlayRequest,
+    chooser: &dyn CardChooser,
+    mut rng: impl Rng,
+) -> Card {
+    return chooser.choose_card(req, &mut rng);
+}
+
+pub fn choose_card_random(req: &(impl ChooseCardToPlayRequest + ?Sized), mut rng: impl Rng) -> Card {
     let legal_plays = req.legal_plays();
     return *legal_plays.choose(&mut rng).unwrap();
 }
 
-pub fn choose_card_avoid_points(req: &impl ChooseCardToPlayRequest, mut rng: impl Rng) -> Card {
+pub fn choose_card_avoid_points(req: &(impl ChooseCardToPlayRequest + ?Sized), mut rng: impl Rng) -> Card {
     let legal_plays = req.legal_plays();
     assert!(legal_plays.len() > 0);
     if legal_plays.len() == 1 {
@@ -369,11 +649,7 @@ pub fn choose_card_avoid_points(req: &impl ChooseCardToPlayRequest, mut rng: imp
     }
 }
 
-fn get_card_to_play(round: &hearts::Round, strategy: &CardToPlayStrategy, mut rng: impl Rng) -> Card {
-    return choose_card_nonrecursive(round, strategy, &mut rng);
-}
-
-fn do_rollout(round: &mut hearts::Round, strategy: &CardToPlayStrategy, mut rng: impl Rng) {
+fn do_rollout(round: &mut hearts::Round, chooser: &dyn CardChooser, mut rng: impl Rng) {
     /*
     println!("Rollout:");
     for (i, p) in round.players.iter().enumerate() {
@@ -389,9 +665,7 @@ fn do_rollout(round: &mut hearts::Round, strategy: &CardToPlayStrategy, mut rng:
             println!("Trick: {:?}", round.current_trick);
         }
         assert!(legal_plays.len() > 0);
-        // We have to split the strategies into recursive and nonrecursive, otherwise the compiler
-        // tries to infinitely recurse.
-        let card_to_play = get_card_to_play(round, strategy, &mut rng);
+        let card_to_play = chooser.choose_card(round, &mut rng);
         round.play_card(&card_to_play);
     }
 }
@@ -408,12 +682,9 @@ fn max_index<T: PartialOrd>(vals: &[T]) -> usize {
     return max_index;
 }
 
-fn make_card_distribution_req(req: &impl ChooseCardToPlayRequest) -> CardDistributionRequest {
+fn make_card_distribution_req(req: &(impl ChooseCardToPlayRequest + ?Sized)) -> CardDistributionRequest {
     let num_players = req.rules().num_players;
-    let mut seen_cards: HashSet<Card> = HashSet::new();
-    for &c in req.hand().iter() {
-        seen_cards.insert(c);
-    }
+    let mut seen_cards = CardSet::from_cards(req.hand());
     let mut voided_suits: Vec<HashSet<Suit>> = Vec::new();
     for _ in 0..num_players {
         voided_suits.push(HashSet::new());
@@ -429,10 +700,10 @@ fn make_card_distribution_req(req: &impl ChooseCardToPlayRequest) -> CardDistrib
             voided_suits[leader].insert(Suit::Diamonds);
             voided_suits[leader].insert(Suit::Clubs);
         }
-        seen_cards.insert(trick_cards[0]);
+        seen_cards.insert(&trick_cards[0]);
         for i in 1..trick_cards.len() {
             let c = trick_cards[i];
-            seen_cards.insert(c);
+            seen_cards.insert(&c);
             if c.suit != trick_suit {
                 voided_suits[(leader + i) % num_players].insert(trick_suit);
             }
@@ -470,13 +741,13 @@ fn make_card_distribution_req(req: &impl ChooseCardToPlayRequest) -> CardDistrib
         constraints.push(CardDistributionPlayerConstraint {
             num_cards: counts[i],
             voided_suits: voided_suits[i].clone(),
-            fixed_cards: HashSet::new(),
+            fixed_cards: CardSet::new(),
         });
     }
     if req.passed_cards().len() > 0 {
         let passed_to = (req.current_player_index() + (req.pass_direction() as usize)) % num_players;
         for c in req.passed_cards().iter() {
-            constraints[passed_to].fixed_cards.insert(*c);
+            constraints[passed_to].fixed_cards.insert(c);
         }
     }
     return CardDistributionRequest {
@@ -486,7 +757,7 @@ fn make_card_distribution_req(req: &impl ChooseCardToPlayRequest) -> CardDistrib
 }
 
 fn possible_round(
-    cc_req: &impl ChooseCardToPlayRequest,
+    cc_req: &(impl ChooseCardToPlayRequest + ?Sized),
     dist_req: &CardDistributionRequest,
     rng: impl Rng,
 ) -> Option<hearts::Round> {
@@ -518,16 +789,37 @@ fn possible_round(
     });
 }
 
-pub fn choose_card_monte_carlo(
+// The chosen card to play, plus the data behind the decision: an
+// equity/score estimate for each legal play (when the strategy produces one)
+// and the number of Monte Carlo rollouts performed (0 for non-MC strategies).
+pub struct CardChoice {
+    pub card: Card,
+    pub evaluations: Vec<(Card, f64)>,
+    pub rollouts: i32,
+}
+
+pub fn choose_card_with_evaluation(
     req: &impl ChooseCardToPlayRequest,
+    chooser: &dyn CardChooser,
+    mut rng: impl Rng,
+) -> CardChoice {
+    return chooser.choose_card_with_evaluation(req, &mut rng);
+}
+
+fn choose_card_monte_carlo_detailed(
+    req: &(impl ChooseCardToPlayRequest + ?Sized),
     mc_params: MonteCarloParams,
-    rollout_strategy: &CardToPlayStrategy,
+    rollout_chooser: &dyn CardChooser,
     mut rng: impl Rng,
-) -> Card {
+) -> CardChoice {
     let legal_plays = req.legal_plays();
     assert!(legal_plays.len() > 0);
     if legal_plays.len() == 1 {
-        return legal_plays[0];
+        return CardChoice {
+            card: legal_plays[0],
+            evaluations: Vec::new(),
+            rollouts: 0,
+        };
     }
     let pnum = req.current_player_index();
     let mut equity_per_play: Vec<f64> = Vec::new();
@@ -546,7 +838,11 @@ pub fn choose_card_monte_carlo(
         let maybe_hypo_round = possible_round(req, &dist_req, &mut rng);
         if maybe_hypo_round.is_none() {
             println!("MC failed, defaulting to choose_card_avoid_points");
-            return choose_card_avoid_points(req, &mut rng);
+            return CardChoice {
+                card: choose_card_avoid_points(req, &mut rng),
+                evaluations: Vec::new(),
+                rollouts: 0,
+            };
         }
         let hypo_round = maybe_hypo_round.unwrap();
         for ci in 0..legal_plays.len() {
@@ -555,7 +851,7 @@ pub fn choose_card_monte_carlo(
             // println!("Card: {}", legal_plays[ci].symbol_string());
             for _r in 0..mc_params.rollouts_per_hand {
                 let mut rh = hypo_copy.clone();
-                do_rollout(&mut rh, &rollout_strategy, &mut rng);
+                do_rollout(&mut rh, rollout_chooser, &mut rng);
                 let round_points = rh.points_taken();
                 let mut scores_after_round = req.scores_before_round().clone();
                 for p in 0..req.rules().num_players {
@@ -568,7 +864,202 @@ pub fn choose_card_monte_carlo(
         }
     }
     // println!("MC equities: {:?}", equity_per_play);
-    return legal_plays[max_index(&equity_per_play)];
+    let rollouts = mc_params.num_hands * mc_params.rollouts_per_hand;
+    let evaluations = legal_plays
+        .iter()
+        .zip(equity_per_play.iter())
+        .map(|(&c, &s)| (c, s))
+        .collect();
+    return CardChoice {
+        card: legal_plays[max_index(&equity_per_play)],
+        evaluations: evaluations,
+        rollouts: rollouts,
+    };
+}
+
+// The `c` in UCB1's `W/N + c*sqrt(ln(N_parent)/N_child)`, used when a
+// chooser (or `chooser_by_name`) doesn't specify its own.
+const DEFAULT_UCT_EXPLORATION_CONSTANT: f64 = std::f64::consts::SQRT_2;
+
+// One decision point in a UCT search tree: `visits` is this node's total
+// visit count (the N_parent of its children's UCB1 scores), and `children`
+// holds a `UctEdge` per legal play that's been expanded so far.
+struct UctNode {
+    visits: u32,
+    children: HashMap<Card, UctEdge>,
+}
+
+impl UctNode {
+    fn new() -> UctNode {
+        return UctNode {
+            visits: 0,
+            children: HashMap::new(),
+        };
+    }
+}
+
+// Stats for one action out of a `UctNode`: how many times it's been taken
+// (N), the total reward backpropagated through it (W), and the subtree
+// reached by taking it.
+struct UctEdge {
+    visits: u32,
+    total_reward: f64,
+    child: UctNode,
+}
+
+// Reward for `deciding_player` if `round` ended up in this state: negative
+// points taken, so lower point totals (including a favorable moon-shot
+// outcome, since `points_taken` already accounts for those) score higher.
+fn uct_reward_for_round(round: &hearts::Round, deciding_player: usize) -> f64 {
+    return -(round.points_taken()[deciding_player] as f64);
+}
+
+// Runs one UCT simulation from `node`, descending `round` (a determinized
+// hand, already past the real decision point by zero or more plies) by
+// selecting the UCB1-best expanded child at each step, until it reaches a
+// legal play that hasn't been expanded from this node yet. That play is
+// expanded as a new child and finished with a rollout by `rollout_chooser`; the resulting
+// reward is backpropagated up through every node visited this call.
+fn uct_iteration<R: Rng>(
+    node: &mut UctNode,
+    round: &mut hearts::Round,
+    deciding_player: usize,
+    rollout_chooser: &dyn CardChooser,
+    exploration_constant: f64,
+    rng: &mut R,
+) -> f64 {
+    if round.is_over() {
+        return uct_reward_for_round(round, deciding_player);
+    }
+    let legal_plays = round.legal_plays();
+    assert!(legal_plays.len() > 0);
+    let unexpanded: Vec<Card> = legal_plays
+        .iter()
+        .cloned()
+        .filter(|c| !node.children.contains_key(c))
+        .collect();
+
+    let reward;
+    if let Some(&action) = unexpanded.choose(rng) {
+        round.play_card(&action).expect("UCT chose an illegal card");
+        reward = if round.is_over() {
+            uct_reward_for_round(round, deciding_player)
+        } else {
+            do_rollout(round, rollout_chooser, &mut *rng);
+            uct_reward_for_round(round, deciding_player)
+        };
+        node.children.insert(
+            action,
+            UctEdge {
+                visits: 1,
+                total_reward: reward,
+                child: UctNode::new(),
+            },
+        );
+    } else {
+        let parent_visits = node.visits.max(1);
+        let mut best_action = legal_plays[0];
+        let mut best_ucb = f64::NEG_INFINITY;
+        for &action in legal_plays.iter() {
+            let edge = &node.children[&action];
+            let ucb = (edge.total_reward / (edge.visits as f64))
+                + exploration_constant * ((parent_visits as f64).ln() / (edge.visits as f64)).sqrt();
+            if ucb > best_ucb {
+                best_ucb = ucb;
+                best_action = action;
+            }
+        }
+        round.play_card(&best_action).expect("UCT chose an illegal card");
+        let edge = node.children.get_mut(&best_action).unwrap();
+        reward = uct_iteration(
+            &mut edge.child,
+            round,
+            deciding_player,
+            rollout_chooser,
+            exploration_constant,
+            rng,
+        );
+        let edge = node.children.get_mut(&best_action).unwrap();
+        edge.visits += 1;
+        edge.total_reward += reward;
+    }
+    node.visits += 1;
+    return reward;
+}
+
+// Like `choose_card_monte_carlo_detailed`, but grows a UCT search tree per
+// determinization instead of sampling flat random rollouts, so later
+// simulations within a determinization concentrate on the lines its own
+// earlier simulations found promising. Visit counts and total reward for
+// each of `req`'s legal plays are summed across all `params.num_hands`
+// determinizations (each tree is discarded once its determinization's
+// `iterations_per_hand` simulations are done), and the play with the
+// highest total visit count is chosen.
+fn choose_card_determinized_uct_detailed(
+    req: &(impl ChooseCardToPlayRequest + ?Sized),
+    params: UctParams,
+    rollout_chooser: &dyn CardChooser,
+    mut rng: impl Rng,
+) -> CardChoice {
+    let legal_plays = req.legal_plays();
+    assert!(legal_plays.len() > 0);
+    if legal_plays.len() == 1 {
+        return CardChoice {
+            card: legal_plays[0],
+            evaluations: Vec::new(),
+            rollouts: 0,
+        };
+    }
+    let pnum = req.current_player_index();
+    let mut visits_per_play: Vec<u32> = vec![0; legal_plays.len()];
+    let mut reward_per_play: Vec<f64> = vec![0.0; legal_plays.len()];
+
+    let dist_req = make_card_distribution_req(req);
+    for _s in 0..params.num_hands {
+        let maybe_hypo_round = possible_round(req, &dist_req, &mut rng);
+        if maybe_hypo_round.is_none() {
+            println!("UCT failed, defaulting to choose_card_avoid_points");
+            return CardChoice {
+                card: choose_card_avoid_points(req, &mut rng),
+                evaluations: Vec::new(),
+                rollouts: 0,
+            };
+        }
+        let hypo_round = maybe_hypo_round.unwrap();
+        let mut root = UctNode::new();
+        for _i in 0..params.iterations_per_hand {
+            let mut round_copy = hypo_round.clone();
+            uct_iteration(
+                &mut root,
+                &mut round_copy,
+                pnum,
+                rollout_chooser,
+                params.exploration_constant,
+                &mut rng,
+            );
+        }
+        for (ci, card) in legal_plays.iter().enumerate() {
+            if let Some(edge) = root.children.get(card) {
+                visits_per_play[ci] += edge.visits;
+                reward_per_play[ci] += edge.total_reward;
+            }
+        }
+    }
+
+    let rollouts = params.num_hands * params.iterations_per_hand;
+    let evaluations = legal_plays
+        .iter()
+        .zip(visits_per_play.iter())
+        .zip(reward_per_play.iter())
+        .map(|((&c, &visits), &reward)| {
+            (c, if visits > 0 { reward / (visits as f64) } else { 0.0 })
+        })
+        .collect();
+    return CardChoice {
+        card: legal_plays[max_index(&visits_per_play)],
+        evaluations: evaluations,
+        rollouts: rollouts,
+    };
 }
 
 // Tests for what card to play are in ffi_test.py.
@@ -580,6 +1071,13 @@ mod test {
         cards_from_str(s).unwrap()
     }
 
+    // For assertions where a set of cards is expected but the order they
+    // come back in isn't meaningful.
+    fn sorted(mut cards: Vec<Card>) -> Vec<Card> {
+        cards.sort_by_key(|c| c.ascii_string());
+        return cards;
+    }
+
     #[test]
     fn test_match_equity() {
         assert_eq!(1.0, match_equity_for_scores(&vec![50, 60, 100, 60], 100, 0));
@@ -611,6 +1109,22 @@ mod test {
         assert!(e4 < e3);
     }
 
+    #[test]
+    fn test_cards_to_pass_request_serde_round_trip() {
+        let req = CardsToPassRequest {
+            rules: hearts::RuleSet::default(),
+            scores_before_round: vec![0, 0, 0, 0],
+            hand: c("AS QS JS AH 8H 2H 6D 5D 4D 3D 6C 5C 4C"),
+            direction: 1,
+            num_cards: 3,
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        let round_tripped: CardsToPassRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped.hand, req.hand);
+        assert_eq!(round_tripped.direction, req.direction);
+        assert_eq!(round_tripped.num_cards, req.num_cards);
+    }
+
     #[test]
     fn test_pass_high_cards() {
         let rules = hearts::RuleSet::default();
@@ -662,4 +1176,42 @@ mod test {
         };
         assert_eq!(choose_cards_to_pass(&req), c("AS KS AH"));
     }
+
+    #[test]
+    fn test_jd_minus_10_keeps_jack_of_diamonds() {
+        let mut rules = hearts::RuleSet::default();
+        rules.jd_minus_10 = true;
+        let req = CardsToPassRequest {
+            rules: rules.clone(),
+            scores_before_round: vec![0, 0, 0, 0],
+            hand: c("AS 5S 4S 3S 8H 5H 3H JD TD 9D 6C 5C 4C"),
+            direction: 1,
+            num_cards: 3,
+        };
+        // Without jd_minus_10 the jack of diamonds would be the top diamond to
+        // pass; with it in effect, keeping it is worth -10 if we can take it
+        // ourselves, so it drops out in favor of the ten and nine of diamonds
+        // and then the highest heart. The order they come back in isn't
+        // meaningful, so compare as sets.
+        assert_eq!(sorted(choose_cards_to_pass(&req)), sorted(c("TD 9D 8H")));
+    }
+
+    #[test]
+    fn test_spot_hearts_weights_high_hearts_more() {
+        let mut rules = hearts::RuleSet::default();
+        rules.spot_hearts = true;
+        let req = CardsToPassRequest {
+            rules: rules.clone(),
+            scores_before_round: vec![0, 0, 0, 0],
+            hand: c("AS 5S 4S 3S AH 5H 3H 6D 5D 4D 6C 5C 4C"),
+            direction: 1,
+            num_cards: 3,
+        };
+        // Under flat heart scoring, passing the ace and five of hearts plus
+        // the six of diamonds leaves a safer hand (danger 12) than clearing
+        // all three hearts (15); spot_hearts' doubled heart risk brings the
+        // two level at 15, and the lower rank total of the three hearts
+        // breaks the tie in favor of emptying the hand of hearts.
+        assert_eq!(choose_cards_to_pass(&req), c("AH 5H 3H"));
+    }
 }
@@ -2,8 +2,13 @@ use crate::card::*;
 use crate::hearts;
 use crate::hearts_ai;
 
-use serde::Deserialize;
+use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
 
 #[derive(Debug)]
 pub struct ParseError {
@@ -28,178 +33,775 @@ impl From<serde_json::error::Error> for ParseError {
     }
 }
 
+// An optional top-level "strategy" object that FFI entry points read
+// alongside their request fields, so embedders can dial AI strength without
+// recompiling. `kind` selects among the choosers registered with
+// `hearts_ai::chooser_by_name`; `mix_ratio`, `num_hands`, and
+// `rollouts_per_hand` configure whichever of them need those parameters.
 #[derive(Deserialize)]
-struct JsonRuleSet {
-    #[serde(default = "hearts::RuleSet::default_num_players")]
-    num_players: usize,
+struct JsonStrategy {
+    #[serde(default = "JsonStrategy::default_kind")]
+    kind: String,
 
-    #[serde(default)]
-    removed_cards: String,
+    #[serde(default = "JsonStrategy::default_mix_ratio")]
+    mix_ratio: f64,
 
-    #[serde(default = "hearts::RuleSet::default_point_limit")]
-    point_limit: u32,
+    #[serde(default = "JsonStrategy::default_num_hands")]
+    num_hands: i32,
 
-    #[serde(default)]
-    points_on_first_trick: bool,
+    #[serde(default = "JsonStrategy::default_rollouts_per_hand")]
+    rollouts_per_hand: i32,
+}
 
-    #[serde(default)]
-    queen_breaks_hearts: bool,
+impl JsonStrategy {
+    fn default_kind() -> String {
+        "monte_carlo_mixed_random_avoid_points".to_string()
+    }
 
-    #[serde(default)]
-    jd_minus_10: bool,
+    fn default_mix_ratio() -> f64 {
+        0.1
+    }
 
-    #[serde(default)]
-    shooting_disabled: bool,
-}
-
-impl JsonRuleSet {
-    fn to_rules(&self) -> Result<hearts::RuleSet, CardError> {
-        return Ok(hearts::RuleSet {
-            num_players: self.num_players,
-            removed_cards: cards_from_str(&self.removed_cards)?,
-            point_limit: self.point_limit,
-            points_on_first_trick: self.points_on_first_trick,
-            queen_breaks_hearts: self.queen_breaks_hearts,
-            jd_minus_10: self.jd_minus_10,
-            moon_shooting: if self.shooting_disabled {
-                hearts::MoonShooting::Disabled
-            } else {
-                hearts::MoonShooting::OpponentsPlus26
-            },
-        });
+    fn default_num_hands() -> i32 {
+        50
+    }
+
+    fn default_rollouts_per_hand() -> i32 {
+        20
+    }
+
+    fn monte_carlo_params(&self) -> hearts_ai::MonteCarloParams {
+        return hearts_ai::MonteCarloParams {
+            num_hands: self.num_hands,
+            rollouts_per_hand: self.rollouts_per_hand,
+        };
+    }
+
+    fn to_chooser(&self) -> Result<Box<dyn hearts_ai::CardChooser>, CardError> {
+        return hearts_ai::chooser_by_name(&self.kind, self.mix_ratio, self.monte_carlo_params())
+            .ok_or_else(|| CardError::new(&format!("Unknown strategy kind: {}", self.kind)));
     }
 }
 
-impl Default for JsonRuleSet {
+impl Default for JsonStrategy {
     fn default() -> Self {
-        let r: JsonRuleSet = serde_json::from_str(r#"{}"#).unwrap();
-        return r;
+        let s: JsonStrategy = serde_json::from_str(r#"{}"#).unwrap();
+        return s;
     }
 }
 
 #[derive(Deserialize)]
-struct JsonCardsToPassRequest {
+struct JsonStrategyWrapper {
     #[serde(default)]
-    rules: JsonRuleSet,
-    scores_before_round: Vec<i32>,
-    hand: String,
-    direction: u32,
-    num_cards: u32,
-}
-
-impl JsonCardsToPassRequest {
-    fn to_request(&self) -> Result<hearts_ai::CardsToPassRequest, CardError> {
-        return Ok(hearts_ai::CardsToPassRequest {
-            rules: self.rules.to_rules()?,
-            scores_before_round: self.scores_before_round.clone(),
-            hand: cards_from_str(&self.hand)?,
-            direction: self.direction,
-            num_cards: self.num_cards,
-        });
+    strategy: JsonStrategy,
+}
+
+// Parses just the optional top-level "strategy" object out of a JSON request
+// body (ignoring whatever other fields, e.g. "hand" or "prev_tricks", the
+// rest of the body contains). Used by FFI entry points that let callers dial
+// AI strategy/strength via JSON instead of recompiling with a hardcoded
+// chooser.
+pub fn parse_strategy(s: &str) -> Result<Box<dyn hearts_ai::CardChooser>, ParseError> {
+    let wrapper: JsonStrategyWrapper = serde_json::from_str(s)?;
+    return Ok(wrapper.strategy.to_chooser()?);
+}
+
+// Like `parse_strategy`, but for the passing phase, which only distinguishes
+// a `"random"` strategy kind from the default danger-score heuristic used by
+// `hearts_ai::choose_cards_to_pass` (passing has no Monte Carlo or
+// avoid-points variant to select). Returns true if the strategy kind is
+// `"random"`.
+pub fn parse_strategy_is_random(s: &str) -> Result<bool, ParseError> {
+    let wrapper: JsonStrategyWrapper = serde_json::from_str(s)?;
+    return Ok(wrapper.strategy.kind == "random");
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrickHistory {
+    #[serde(default)]
+    pub rules: hearts::RuleSet,
+    pub tricks: Vec<hearts::Trick>,
+}
+
+impl TrickHistory {
+    pub fn points_taken(&self) -> Vec<i32> {
+        return hearts::points_for_tricks(&self.tricks, &self.rules);
     }
 }
 
-#[derive(Deserialize)]
-struct JsonTrick {
-    leader: usize,
-    cards: String,
+fn has_duplicate_cards(cards: &[Card]) -> bool {
+    let mut seen: HashSet<Card> = HashSet::new();
+    for &c in cards.iter() {
+        if !seen.insert(c) {
+            return true;
+        }
+    }
+    return false;
 }
 
-impl JsonTrick {
-    fn to_trick(&self) -> Result<hearts::Trick, CardError> {
-        let cards = cards_from_str(&self.cards)?;
-        let winner = (self.leader + hearts::trick_winner_index(&cards)) % cards.len();
-        return Ok(hearts::Trick {
-            leader: self.leader,
-            cards: cards,
-            winner: winner,
-        });
+// Checks that a parsed CardsToPassRequest is internally consistent (as
+// opposed to serde's parsing, which only checks that the card strings parse),
+// so the AI is never fed a request describing an impossible game state.
+fn validate_cards_to_pass_request(req: &hearts_ai::CardsToPassRequest) -> Result<(), ParseError> {
+    let rules = &req.rules;
+    if req.scores_before_round.len() != rules.num_players {
+        return Err(ParseError::new(&format!(
+            "scores_before_round has {} entries but there are {} players",
+            req.scores_before_round.len(),
+            rules.num_players
+        )));
+    }
+    if (req.direction as usize) >= rules.num_players {
+        return Err(ParseError::new(&format!(
+            "direction {} is not a valid pass direction for {} players",
+            req.direction, rules.num_players
+        )));
+    }
+    if (req.num_cards as usize) > req.hand.len() {
+        return Err(ParseError::new(&format!(
+            "num_cards {} exceeds the {} cards in hand",
+            req.num_cards,
+            req.hand.len()
+        )));
     }
+    if has_duplicate_cards(&req.hand) {
+        return Err(ParseError::new("hand contains a duplicate card"));
+    }
+    return Ok(());
+}
 
-    fn to_tricks(jts: &[JsonTrick]) -> Result<Vec<hearts::Trick>, CardError> {
-        let mut tricks: Vec<hearts::Trick> = Vec::new();
-        for jt in jts.iter() {
-            tricks.push(jt.to_trick()?);
+// Checks that a parsed CardToPlayDirectRequest is internally consistent: no
+// card appears twice across the hand and tricks, the current trick's leader
+// and card count are legal for the player count, and scores_before_round and
+// pass_direction match the configured RuleSet.
+fn validate_card_to_play_request(
+    req: &hearts_ai::CardToPlayDirectRequest,
+) -> Result<(), ParseError> {
+    let rules = &req.rules;
+    if req.scores_before_round.len() != rules.num_players {
+        return Err(ParseError::new(&format!(
+            "scores_before_round has {} entries but there are {} players",
+            req.scores_before_round.len(),
+            rules.num_players
+        )));
+    }
+    if req.current_trick.leader >= rules.num_players {
+        return Err(ParseError::new(&format!(
+            "current_trick leader {} is out of range for {} players",
+            req.current_trick.leader, rules.num_players
+        )));
+    }
+    if req.current_trick.cards.len() >= rules.num_players {
+        return Err(ParseError::new(
+            "current_trick already has a full trick's worth of cards",
+        ));
+    }
+    for t in req.prev_tricks.iter() {
+        if t.leader >= rules.num_players || t.cards.len() != rules.num_players {
+            return Err(ParseError::new("prev_tricks contains an illegal trick"));
         }
-        return Ok(tricks);
     }
+    let mut all_cards: Vec<Card> = req.hand.clone();
+    for t in req.prev_tricks.iter() {
+        all_cards.extend(t.cards.iter().cloned());
+    }
+    all_cards.extend(req.current_trick.cards.iter().cloned());
+    if has_duplicate_cards(&all_cards) {
+        return Err(ParseError::new(
+            "the same card appears more than once across hand and tricks",
+        ));
+    }
+    if (req.pass_direction as usize) >= rules.num_players {
+        return Err(ParseError::new(&format!(
+            "pass_direction {} is not valid for {} players",
+            req.pass_direction, rules.num_players
+        )));
+    }
+    return Ok(());
+}
 
-    fn to_trick_in_progress(&self) -> Result<hearts::TrickInProgress, CardError> {
-        return Ok(hearts::TrickInProgress {
-            leader: self.leader,
-            cards: cards_from_str(&self.cards)?,
-        });
+pub fn parse_cards_to_pass_request(s: &str) -> Result<hearts_ai::CardsToPassRequest, ParseError> {
+    let parsed: hearts_ai::CardsToPassRequest = serde_json::from_str(s)?;
+    validate_cards_to_pass_request(&parsed)?;
+    return Ok(parsed);
+}
+
+pub fn parse_card_to_play_request(s: &str) -> Result<hearts_ai::CardToPlayDirectRequest, ParseError> {
+    let parsed: hearts_ai::CardToPlayDirectRequest = serde_json::from_str(s)?;
+    validate_card_to_play_request(&parsed)?;
+    return Ok(parsed);
+}
+
+pub fn parse_trick_history(s: &str) -> Result<TrickHistory, ParseError> {
+    return Ok(serde_json::from_str(s)?);
+}
+
+#[derive(Serialize)]
+struct JsonCardEvaluation {
+    card: String,
+    score: f64,
+}
+
+#[derive(Serialize)]
+pub struct JsonCardToPlayResponse {
+    card: String,
+    evaluations: Vec<JsonCardEvaluation>,
+    rollouts: i32,
+}
+
+impl JsonCardToPlayResponse {
+    fn from_choice(choice: &hearts_ai::CardChoice) -> JsonCardToPlayResponse {
+        return JsonCardToPlayResponse {
+            card: choice.card.ascii_string(),
+            evaluations: choice
+                .evaluations
+                .iter()
+                .map(|&(c, score)| JsonCardEvaluation {
+                    card: c.ascii_string(),
+                    score: score,
+                })
+                .collect(),
+            rollouts: choice.rollouts,
+        };
     }
 }
 
+// An optional top-level "seed" field, usable alongside any other request
+// struct parsed from the same JSON string, so a caller can opt into a
+// deterministic StdRng instead of thread_rng() for reproducible Monte Carlo
+// decisions (e.g. debugging, regression tests, or stored replays).
 #[derive(Deserialize)]
-struct JsonCardToPlayRequest {
+struct JsonSeed {
     #[serde(default)]
-    rules: JsonRuleSet,
-    scores_before_round: Vec<i32>,
-    hand: String,
-    prev_tricks: Vec<JsonTrick>,
-    current_trick: JsonTrick,
-    pass_direction: u32,
-    passed_cards: String,
-    received_cards: String,
-}
-
-impl JsonCardToPlayRequest {
-    fn to_request(&self) -> Result<hearts_ai::CardToPlayDirectRequest, CardError> {
-        return Ok(hearts_ai::CardToPlayDirectRequest {
-            rules: self.rules.to_rules()?,
-            scores_before_round: self.scores_before_round.clone(),
-            hand: cards_from_str(&self.hand)?,
-            prev_tricks: JsonTrick::to_tricks(&self.prev_tricks)?,
-            current_trick: self.current_trick.to_trick_in_progress()?,
-            pass_direction: self.pass_direction,
-            passed_cards: cards_from_str(&self.passed_cards)?,
-            received_cards: cards_from_str(&self.received_cards)?,
-        });
+    seed: Option<u64>,
+}
+
+// Parses just the optional top-level "seed" field out of a JSON request body
+// (ignoring whatever other fields the rest of the body contains).
+pub fn parse_seed(s: &str) -> Result<Option<u64>, ParseError> {
+    let wrapper: JsonSeed = serde_json::from_str(s)?;
+    return Ok(wrapper.seed);
+}
+
+// Parses `s` as a CardToPlayDirectRequest, runs `strategy` on it, and returns
+// the chosen card (and, for Monte Carlo strategies, the per-card equity
+// estimates and rollout count behind the decision) serialized as a
+// JsonCardToPlayResponse.
+// If `s` has a "seed" field, the Monte Carlo rollouts (if any) are driven by a
+// StdRng seeded from it instead of thread_rng(), for reproducible decisions.
+pub fn card_to_play_response_json(
+    s: &str,
+    strategy: &dyn hearts_ai::CardChooser,
+) -> Result<String, ParseError> {
+    let req = parse_card_to_play_request(s)?;
+    let choice = match parse_seed(s)? {
+        Some(seed) => {
+            let rng: StdRng = SeedableRng::seed_from_u64(seed);
+            hearts_ai::choose_card_with_evaluation(&req, strategy, rng)
+        }
+        None => hearts_ai::choose_card_with_evaluation(&req, strategy, thread_rng()),
+    };
+    let response = JsonCardToPlayResponse::from_choice(&choice);
+    return Ok(serde_json::to_string(&response)?);
+}
+
+#[derive(Serialize)]
+struct JsonCardScore {
+    card: String,
+    score: i32,
+}
+
+#[derive(Serialize)]
+pub struct JsonCardsToPassResponse {
+    cards: String,
+    evaluations: Vec<JsonCardScore>,
+}
+
+// Parses `s` as a CardsToPassRequest, runs `choose_cards_to_pass_with_scores`
+// on it, and returns the chosen cards (in "2C 8D" notation) and the danger
+// score computed for every card in hand, serialized as a JsonCardsToPassResponse.
+pub fn cards_to_pass_response_json(s: &str) -> Result<String, ParseError> {
+    let req = parse_cards_to_pass_request(s)?;
+    let (cards, scores) = hearts_ai::choose_cards_to_pass_with_scores(&req);
+    let response = JsonCardsToPassResponse {
+        cards: ascii_str_from_cards(&cards),
+        evaluations: scores
+            .iter()
+            .map(|&(c, score)| JsonCardScore {
+                card: c.ascii_string(),
+                score: score,
+            })
+            .collect(),
+    };
+    return Ok(serde_json::to_string(&response)?);
+}
+
+// One completed round: the hands as dealt, the pass, every trick played, and
+// the scores after the round (both for the round alone and cumulative).
+#[derive(Serialize, Deserialize)]
+pub struct RoundRecord {
+    #[serde(with = "vec_cards_as_str")]
+    pub hands: Vec<Vec<Card>>,
+    pub pass_direction: u32,
+    #[serde(with = "vec_cards_as_str")]
+    pub passed_cards: Vec<Vec<Card>>,
+    #[serde(with = "vec_cards_as_str")]
+    pub received_cards: Vec<Vec<Card>>,
+    pub tricks: Vec<hearts::Trick>,
+    pub round_points: Vec<i32>,
+    pub cumulative_scores: Vec<i32>,
+}
+
+// Reconstructs the hand each player was originally dealt for a just-finished
+// `hearts::Round`. `Player::hand` is empty by the time a round is over, so
+// the dealt hand is rebuilt from what it can be proven the player held:
+// every card they played (read off `prev_tricks`, using each trick's
+// `leader` to attribute cards to seats) plus what they passed away, minus
+// what they received from someone else.
+fn dealt_hands_from_round(round: &hearts::Round) -> Vec<Vec<Card>> {
+    let num_players = round.rules.num_players;
+    let mut hands: Vec<Vec<Card>> = vec![Vec::new(); num_players];
+    for t in round.prev_tricks.iter() {
+        for (i, &card) in t.cards.iter().enumerate() {
+            hands[(t.leader + i) % num_players].push(card);
+        }
+    }
+    for (p, player) in round.players.iter().enumerate() {
+        hands[p].extend(player.passed_cards.iter().cloned());
+        hands[p].retain(|c| !player.received_cards.contains(c));
     }
+    return hands;
 }
 
-pub struct TrickHistory {
+// Builds a `RoundRecord` from a completed `hearts::Round`, for exporting a
+// just-played round without requiring the caller to have tracked the deal
+// or trick history themselves. `cumulative_scores` is the match score after
+// this round (i.e. including `round.points_taken()`).
+pub fn round_record_from_round(round: &hearts::Round, cumulative_scores: &[i32]) -> RoundRecord {
+    assert!(round.is_over(), "round_record_from_round requires a completed round");
+    return RoundRecord {
+        hands: dealt_hands_from_round(round),
+        pass_direction: round.pass_direction,
+        passed_cards: round.players.iter().map(|p| p.passed_cards.clone()).collect(),
+        received_cards: round.players.iter().map(|p| p.received_cards.clone()).collect(),
+        tricks: round.prev_tricks.clone(),
+        round_points: round.points_taken(),
+        cumulative_scores: cumulative_scores.to_vec(),
+    };
+}
+
+// Serializes a completed `hearts::Round` as a single JSON `RoundRecord`, in
+// the same shape `write_replay` uses for each entry of `GameReplay.rounds`.
+// This lets a bot-match runner log a round as soon as it finishes, without
+// assembling a whole `GameReplay` up front.
+pub fn serialize_round(round: &hearts::Round, cumulative_scores: &[i32]) -> String {
+    let record = round_record_from_round(round, cumulative_scores);
+    return serde_json::to_string(&record).unwrap();
+}
+
+// Everything an FFI caller already knows about a round it just finished
+// driving through `card_to_play_from_json`/`cards_to_pass_from_json`: the
+// hands as dealt, the pass, and every trick played. Mirrors `RoundRecord`,
+// but takes `scores_before_round` instead of `cumulative_scores` since the
+// round's own points haven't been computed yet.
+#[derive(Serialize, Deserialize)]
+pub struct RoundResultRequest {
+    #[serde(default)]
     pub rules: hearts::RuleSet,
+    pub scores_before_round: Vec<i32>,
+    #[serde(with = "vec_cards_as_str")]
+    pub hands: Vec<Vec<Card>>,
+    pub pass_direction: u32,
+    #[serde(with = "vec_cards_as_str")]
+    pub passed_cards: Vec<Vec<Card>>,
+    #[serde(with = "vec_cards_as_str")]
+    pub received_cards: Vec<Vec<Card>>,
     pub tricks: Vec<hearts::Trick>,
 }
 
-impl TrickHistory {
-    pub fn points_taken(&self) -> Vec<i32> {
-        return hearts::points_for_tricks(&self.tricks, &self.rules);
-    }
+pub fn parse_round_result_request(s: &str) -> Result<RoundResultRequest, ParseError> {
+    return Ok(serde_json::from_str(s)?);
 }
 
-#[derive(Deserialize)]
-struct JsonTrickHistory {
+fn round_record_from_result_request(req: &RoundResultRequest) -> RoundRecord {
+    let round_points = hearts::points_for_tricks(&req.tricks, &req.rules);
+    let cumulative_scores: Vec<i32> = req
+        .scores_before_round
+        .iter()
+        .zip(round_points.iter())
+        .map(|(&before, &taken)| before + taken)
+        .collect();
+    return RoundRecord {
+        hands: req.hands.clone(),
+        pass_direction: req.pass_direction,
+        passed_cards: req.passed_cards.clone(),
+        received_cards: req.received_cards.clone(),
+        tricks: req.tricks.clone(),
+        round_points: round_points,
+        cumulative_scores: cumulative_scores,
+    };
+}
+
+// Parses `s` as a RoundResultRequest and returns the resulting
+// RoundRecord serialized as JSON, in the same shape `write_replay` uses for
+// each entry of `GameReplay.rounds`. This is the FFI-friendly counterpart
+// to `serialize_round`, for callers driving a round entirely through JSON
+// requests and so never holding a `hearts::Round` of their own.
+pub fn round_replay_response_json(s: &str) -> Result<String, ParseError> {
+    let req = parse_round_result_request(s)?;
+    let record = round_record_from_result_request(&req);
+    return Ok(serde_json::to_string(&record)?);
+}
+
+// A full match, as a sequence of rounds under a single rule set. This is the
+// format bot-vs-bot runners can write their game logs in, so a recorded match
+// can be diffed across AI versions or replayed as a regression fixture.
+#[derive(Serialize, Deserialize)]
+pub struct GameReplay {
     #[serde(default)]
-    rules: JsonRuleSet,
-    tricks: Vec<JsonTrick>,
+    pub rules: hearts::RuleSet,
+    pub rounds: Vec<RoundRecord>,
 }
 
-impl JsonTrickHistory {
-    fn to_history(&self) -> Result<TrickHistory, ParseError> {
-        return Ok(TrickHistory {
-            rules: self.rules.to_rules()?,
-            tricks: JsonTrick::to_tricks(&self.tricks)?,
+pub fn write_replay(replay: &GameReplay) -> String {
+    return serde_json::to_string(replay).unwrap();
+}
+
+pub fn parse_replay(s: &str) -> Result<GameReplay, ParseError> {
+    return Ok(serde_json::from_str(s)?);
+}
+
+// One step of a completed round, in chronological order: the initial deal
+// (recorded as a seed, so the hands can be reconstructed), each player's
+// pass, each card played (with the seat that played it), and the winner of
+// each resulting trick. A round's moves can be re-simulated from this log via
+// `replay_move_log`, which re-validates every play against `legal_plays`
+// rather than trusting the log blindly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoundEvent {
+    Deal { seed: u64, pass_direction: u32 },
+    Pass { player: usize, cards: Vec<Card> },
+    Play { player: usize, card: Card },
+    TrickWon { winner: usize },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonRoundEvent {
+    Deal { seed: u64, pass_direction: u32 },
+    Pass { player: usize, cards: String },
+    Play { player: usize, card: String },
+    TrickWon { winner: usize },
+}
+
+impl JsonRoundEvent {
+    fn from_event(e: &RoundEvent) -> JsonRoundEvent {
+        return match e {
+            RoundEvent::Deal { seed, pass_direction } => JsonRoundEvent::Deal {
+                seed: *seed,
+                pass_direction: *pass_direction,
+            },
+            RoundEvent::Pass { player, cards } => JsonRoundEvent::Pass {
+                player: *player,
+                cards: ascii_str_from_cards(cards),
+            },
+            RoundEvent::Play { player, card } => JsonRoundEvent::Play {
+                player: *player,
+                card: card.ascii_string(),
+            },
+            RoundEvent::TrickWon { winner } => JsonRoundEvent::TrickWon { winner: *winner },
+        };
+    }
+
+    fn to_event(&self) -> Result<RoundEvent, CardError> {
+        return Ok(match self {
+            JsonRoundEvent::Deal { seed, pass_direction } => RoundEvent::Deal {
+                seed: *seed,
+                pass_direction: *pass_direction,
+            },
+            JsonRoundEvent::Pass { player, cards } => RoundEvent::Pass {
+                player: *player,
+                cards: cards_from_str(cards)?,
+            },
+            JsonRoundEvent::Play { player, card } => RoundEvent::Play {
+                player: *player,
+                card: Card::from(card)?,
+            },
+            JsonRoundEvent::TrickWon { winner } => RoundEvent::TrickWon { winner: *winner },
         });
     }
 }
 
-pub fn parse_cards_to_pass_request(s: &str) -> Result<hearts_ai::CardsToPassRequest, ParseError> {
-    let req: JsonCardsToPassRequest = serde_json::from_str(s)?;
-    return Ok(req.to_request()?);
+pub fn write_move_log(events: &[RoundEvent]) -> String {
+    let json: Vec<JsonRoundEvent> = events.iter().map(JsonRoundEvent::from_event).collect();
+    return serde_json::to_string(&json).unwrap();
 }
 
-pub fn parse_card_to_play_request(s: &str) -> Result<hearts_ai::CardToPlayDirectRequest, ParseError> {
-    let req: JsonCardToPlayRequest = serde_json::from_str(s)?;
-    return Ok(req.to_request()?);
+pub fn parse_move_log(s: &str) -> Result<Vec<RoundEvent>, ParseError> {
+    let json: Vec<JsonRoundEvent> = serde_json::from_str(s)?;
+    let mut events = Vec::new();
+    for e in json.iter() {
+        events.push(e.to_event()?);
+    }
+    return Ok(events);
 }
 
-pub fn parse_trick_history(s: &str) -> Result<TrickHistory, ParseError> {
-    let j: JsonTrickHistory = serde_json::from_str(s)?;
-    return Ok(j.to_history()?);
+// Reconstructs a Round by replaying a move log from scratch: the deal is
+// reshuffled from the recorded seed, and each event is checked against the
+// round's actual state (via `can_pass_cards`/`legal_plays`/the resulting
+// trick winner) before being applied, so a corrupted or hand-edited log is
+// rejected instead of silently desyncing from what's being replayed.
+pub fn replay_move_log(
+    rules: &hearts::RuleSet,
+    events: &[RoundEvent],
+) -> Result<hearts::Round, ParseError> {
+    let mut events = events.iter();
+    let (seed, pass_direction) = match events.next() {
+        Some(RoundEvent::Deal { seed, pass_direction }) => (*seed, *pass_direction),
+        _ => return Err(ParseError::new("Move log must start with a Deal event")),
+    };
+    let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+    let mut deck = Deck::new();
+    deck.shuffle(&mut rng);
+    let scores: Vec<i32> = vec![0; rules.num_players];
+    let mut round = hearts::Round::deal(&deck, rules, &scores, pass_direction);
+
+    for event in events {
+        match event {
+            RoundEvent::Deal { .. } => {
+                return Err(ParseError::new("Unexpected second Deal event"));
+            }
+            RoundEvent::Pass { player, cards } => {
+                if !round.can_pass_cards(*player, cards) {
+                    return Err(ParseError::new("Illegal cards to pass in move log"));
+                }
+                round.set_passed_cards_for_player(*player, cards);
+                if round.ready_to_pass_cards() {
+                    round.pass_cards();
+                }
+            }
+            RoundEvent::Play { player, card } => {
+                if round.current_player_index() != *player {
+                    return Err(ParseError::new("Move log plays out of turn"));
+                }
+                if !round.legal_plays().contains(card) {
+                    return Err(ParseError::new("Move log contains an illegal play"));
+                }
+                round
+                    .play_card(card)
+                    .map_err(|_| ParseError::new("Move log contains an illegal play"))?;
+            }
+            RoundEvent::TrickWon { winner } => {
+                let actual = round.prev_tricks.last().map(|t| t.winner);
+                if actual != Some(*winner) {
+                    return Err(ParseError::new(
+                        "Move log's recorded trick winner doesn't match replay",
+                    ));
+                }
+            }
+        }
+    }
+    return Ok(round);
+}
+
+// Stateful multi-round session protocol. Instead of every request resending
+// the full game context, a `StartGame` message returns a `game_id`, and
+// subsequent `Pass`/`PlayCard` messages reference it; the engine tracks the
+// round (and cumulative scores) server-side, validating that each submitted
+// card is legal given the state it's holding for that game.
+struct GameSession {
+    rules: hearts::RuleSet,
+    round: hearts::Round,
+    scores: Vec<i32>,
+}
+
+fn sessions() -> &'static Mutex<HashMap<u64, GameSession>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<u64, GameSession>>> = OnceLock::new();
+    return SESSIONS.get_or_init(|| Mutex::new(HashMap::new()));
+}
+
+fn next_game_id() -> u64 {
+    static NEXT_ID: OnceLock<Mutex<u64>> = OnceLock::new();
+    let counter = NEXT_ID.get_or_init(|| Mutex::new(1));
+    let mut next = counter.lock().unwrap();
+    let id = *next;
+    *next += 1;
+    return id;
+}
+
+#[derive(Deserialize)]
+struct JsonStartGameMessage {
+    #[serde(default)]
+    rules: hearts::RuleSet,
+    #[serde(default)]
+    pass_direction: u32,
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct JsonStartGameResponse {
+    game_id: u64,
+    hands: Vec<String>,
+    pass_direction: u32,
+    status: String,
+    current_player: usize,
+}
+
+#[derive(Deserialize)]
+struct JsonPassMessage {
+    game_id: u64,
+    player: usize,
+    cards: String,
+}
+
+#[derive(Deserialize)]
+struct JsonPlayCardMessage {
+    game_id: u64,
+    player: usize,
+    card: String,
+}
+
+#[derive(Serialize)]
+pub struct JsonGameState {
+    game_id: u64,
+    status: String,
+    current_player: usize,
+    current_trick_leader: usize,
+    current_trick_cards: String,
+    scores: Vec<i32>,
+    round_points: Option<Vec<i32>>,
+}
+
+fn status_string(round: &hearts::Round) -> String {
+    return match round.status {
+        hearts::RoundStatus::Passing => "passing".to_string(),
+        hearts::RoundStatus::Playing => {
+            if round.is_over() {
+                "round_complete".to_string()
+            } else {
+                "playing".to_string()
+            }
+        }
+    };
+}
+
+fn state_for_session(game_id: u64, session: &GameSession) -> JsonGameState {
+    let round = &session.round;
+    let round_points = if round.is_over() {
+        Some(round.points_taken())
+    } else {
+        None
+    };
+    return JsonGameState {
+        game_id: game_id,
+        status: status_string(round),
+        current_player: round.current_player_index(),
+        current_trick_leader: round.current_trick.leader,
+        current_trick_cards: ascii_str_from_cards(&round.current_trick.cards),
+        scores: session.scores.clone(),
+        round_points: round_points,
+    };
+}
+
+// Deals a new round, stores it under a fresh `game_id`, and returns the dealt
+// hands and initial state as a JsonStartGameResponse.
+pub fn start_game_json(s: &str) -> Result<String, ParseError> {
+    let msg: JsonStartGameMessage = serde_json::from_str(s)?;
+    let rules = msg.rules;
+    let mut deck = Deck::new();
+    match msg.seed {
+        Some(seed) => {
+            let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+            deck.shuffle(&mut rng);
+        }
+        None => deck.shuffle(thread_rng()),
+    }
+    let scores: Vec<i32> = vec![0; rules.num_players];
+    let round = hearts::Round::deal(&deck, &rules, &scores, msg.pass_direction);
+    let hands: Vec<String> = round
+        .players
+        .iter()
+        .map(|p| ascii_str_from_cards(&p.hand))
+        .collect();
+    let game_id = next_game_id();
+    let status = status_string(&round);
+    let current_player = round.current_player_index();
+    sessions().lock().unwrap().insert(
+        game_id,
+        GameSession {
+            rules: rules,
+            round: round,
+            scores: scores,
+        },
+    );
+    let response = JsonStartGameResponse {
+        game_id: game_id,
+        hands: hands,
+        pass_direction: msg.pass_direction,
+        status: status,
+        current_player: current_player,
+    };
+    return Ok(serde_json::to_string(&response)?);
+}
+
+// Submits the cards a player wants to pass for the game's current round. Once
+// every player has passed, the engine performs the exchange and moves the
+// round into the `Playing` phase.
+pub fn pass_cards_json(s: &str) -> Result<String, ParseError> {
+    let msg: JsonPassMessage = serde_json::from_str(s)?;
+    let cards = cards_from_str(&msg.cards)?;
+    let mut sessions = sessions().lock().unwrap();
+    let session = sessions
+        .get_mut(&msg.game_id)
+        .ok_or_else(|| ParseError::new("Unknown game_id"))?;
+    if !session.round.can_pass_cards(msg.player, &cards) {
+        return Err(ParseError::new("Illegal cards to pass"));
+    }
+    session.round.set_passed_cards_for_player(msg.player, &cards);
+    if session.round.ready_to_pass_cards() {
+        session.round.pass_cards();
+    }
+    return Ok(serde_json::to_string(&state_for_session(
+        msg.game_id,
+        session,
+    ))?);
+}
+
+// Plays a card on behalf of `player` in the game's current trick, validating
+// that it is actually that player's turn and that the card is legal before
+// applying it, then returns the updated state (including final round points
+// once the round is complete).
+pub fn play_card_json(s: &str) -> Result<String, ParseError> {
+    let msg: JsonPlayCardMessage = serde_json::from_str(s)?;
+    let card = Card::from(&msg.card)?;
+    let mut sessions = sessions().lock().unwrap();
+    let session = sessions
+        .get_mut(&msg.game_id)
+        .ok_or_else(|| ParseError::new("Unknown game_id"))?;
+    if session.round.status != hearts::RoundStatus::Playing {
+        return Err(ParseError::new("Round is not in the playing phase"));
+    }
+    if session.round.current_player_index() != msg.player {
+        return Err(ParseError::new("It is not that player's turn"));
+    }
+    if !session.round.legal_plays().contains(&card) {
+        return Err(ParseError::new("Illegal card"));
+    }
+    session
+        .round
+        .play_card(&card)
+        .map_err(|_| ParseError::new("Illegal card"))?;
+    if session.round.is_over() {
+        let round_points = session.round.points_taken();
+        for p in 0..session.rules.num_players {
+            session.scores[p] += round_points[p];
+        }
+    }
+    return Ok(serde_json::to_string(&state_for_session(
+        msg.game_id,
+        session,
+    ))?);
 }
 
 #[cfg(test)]
@@ -222,6 +824,71 @@ mod test {
         assert_eq!(req.hand.len(), 4);
     }
 
+    #[test]
+    fn test_parse_strategy_defaults_to_monte_carlo_mixed() {
+        let strategy = parse_strategy("{}").unwrap();
+        let mc = strategy
+            .as_any()
+            .downcast_ref::<hearts_ai::MonteCarloChooser>()
+            .expect("Expected MonteCarloChooser");
+        assert_eq!(mc.params.num_hands, 50);
+        assert_eq!(mc.params.rollouts_per_hand, 20);
+        let rollout = mc
+            .rollout_chooser
+            .as_any()
+            .downcast_ref::<hearts_ai::MixedRandomAvoidPointsChooser>()
+            .expect("Expected MixedRandomAvoidPointsChooser rollout");
+        assert_eq!(rollout.p_random, 0.1);
+    }
+
+    #[test]
+    fn test_parse_strategy_selects_kind_and_params() {
+        let strategy = parse_strategy(
+            r#"{"strategy": {"kind": "monte_carlo_avoid_points", "num_hands": 10, "rollouts_per_hand": 5}}"#,
+        )
+        .unwrap();
+        let mc = strategy
+            .as_any()
+            .downcast_ref::<hearts_ai::MonteCarloChooser>()
+            .expect("Expected MonteCarloChooser");
+        assert_eq!(mc.params.num_hands, 10);
+        assert_eq!(mc.params.rollouts_per_hand, 5);
+        mc.rollout_chooser
+            .as_any()
+            .downcast_ref::<hearts_ai::AvoidPointsChooser>()
+            .expect("Expected AvoidPointsChooser rollout");
+    }
+
+    #[test]
+    fn test_parse_strategy_selects_determinized_uct() {
+        let strategy = parse_strategy(
+            r#"{"strategy": {"kind": "determinized_uct", "num_hands": 10, "rollouts_per_hand": 5}}"#,
+        )
+        .unwrap();
+        let uct = strategy
+            .as_any()
+            .downcast_ref::<hearts_ai::DeterminizedUctChooser>()
+            .expect("Expected DeterminizedUctChooser");
+        assert_eq!(uct.params.num_hands, 10);
+        assert_eq!(uct.params.iterations_per_hand, 5);
+        uct.rollout_chooser
+            .as_any()
+            .downcast_ref::<hearts_ai::AvoidPointsChooser>()
+            .expect("Expected AvoidPointsChooser rollout");
+    }
+
+    #[test]
+    fn test_parse_strategy_rejects_unknown_kind() {
+        let result = parse_strategy(r#"{"strategy": {"kind": "bogus"}}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_strategy_is_random() {
+        assert!(!parse_strategy_is_random("{}").unwrap());
+        assert!(parse_strategy_is_random(r#"{"strategy": {"kind": "random"}}"#).unwrap());
+    }
+
     #[test]
     fn test_parse_play_request() {
         let req = parse_card_to_play_request(
@@ -285,7 +952,7 @@ mod test {
                 "rules": {
                     "point_limit": 42,
                     "jd_minus_10": true,
-                    "shooting_disabled": true
+                    "moon_shooting": "disabled"
                 },
                 "scores_before_round": [30, 10, 20, 40],
                 "hand": "2C 8D AS QD",
@@ -316,9 +983,9 @@ mod test {
                     "points_on_first_trick": true,
                     "queen_breaks_hearts": true,
                     "jd_minus_10": false,
-                    "shooting_disabled": false
+                    "moon_shooting": "opponents_plus_26"
                 },
-                "scores_before_round": [30, 10, 20, 40],
+                "scores_before_round": [30, 10, 20, 40, 0],
                 "hand": "2C 8D AS QD",
                 "direction": 1,
                 "num_cards": 3
@@ -337,7 +1004,426 @@ mod test {
             queen_breaks_hearts: true,
             jd_minus_10: false,
             moon_shooting: hearts::MoonShooting::OpponentsPlus26,
+            spot_hearts: false,
+            heart_value: hearts::RuleSet::default_heart_value(),
+            queen_of_spades_value: hearts::RuleSet::default_queen_of_spades_value(),
+            point_overrides: Vec::new(),
+            pass_direction_schedule: hearts::RuleSet::default_pass_direction_schedule(),
         };
         assert_eq!(req.rules, expected);
     }
+
+    #[test]
+    fn test_spot_hearts_and_pass_direction_schedule_round_trip() {
+        let req = parse_cards_to_pass_request(
+            r#"
+            {
+                "rules": {
+                    "spot_hearts": true,
+                    "pass_direction_schedule": "left right"
+                },
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D AS QD",
+                "direction": 1,
+                "num_cards": 3
+            }
+        "#,
+        )
+        .unwrap();
+        assert!(req.rules.spot_hearts);
+        assert_eq!(
+            req.rules.pass_direction_schedule,
+            vec![hearts::PassDirectionKind::Left, hearts::PassDirectionKind::Right]
+        );
+
+        let json = serde_json::to_string(&req.rules).unwrap();
+        assert!(json.contains(r#""pass_direction_schedule":"left right""#));
+    }
+
+    #[test]
+    fn test_point_overrides_round_trip() {
+        let req = parse_cards_to_pass_request(
+            r#"
+            {
+                "rules": {
+                    "point_overrides": "TD=10 9D=5"
+                },
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D AS QD",
+                "direction": 1,
+                "num_cards": 3
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            req.rules.point_overrides,
+            vec![(Card::from("TD").unwrap(), 10), (Card::from("9D").unwrap(), 5)]
+        );
+
+        let json = serde_json::to_string(&req.rules).unwrap();
+        assert!(json.contains(r#""point_overrides":"TD=10 9D=5""#));
+    }
+
+    #[test]
+    fn test_replay_round_trip() {
+        let tricks = vec![
+            hearts::Trick {
+                leader: 0,
+                cards: cards_from_str("2C AC KC QC").unwrap(),
+                winner: 1,
+            },
+            hearts::Trick {
+                leader: 1,
+                cards: cards_from_str("3D 6D QS 5D").unwrap(),
+                winner: 2,
+            },
+        ];
+        let replay = GameReplay {
+            rules: hearts::RuleSet::default(),
+            rounds: vec![RoundRecord {
+                hands: vec![
+                    cards_from_str("2C 8D AS QD").unwrap(),
+                    cards_from_str("3D 6D QS 5D").unwrap(),
+                    cards_from_str("AC KC QC 4D").unwrap(),
+                    cards_from_str("2D 9H KD AH").unwrap(),
+                ],
+                pass_direction: 1,
+                passed_cards: vec![
+                    cards_from_str("AS").unwrap(),
+                    cards_from_str("QS").unwrap(),
+                    cards_from_str("AC").unwrap(),
+                    cards_from_str("AH").unwrap(),
+                ],
+                received_cards: vec![
+                    cards_from_str("AH").unwrap(),
+                    cards_from_str("AS").unwrap(),
+                    cards_from_str("QS").unwrap(),
+                    cards_from_str("AC").unwrap(),
+                ],
+                tricks: tricks.clone(),
+                round_points: vec![0, 14, 0, 0],
+                cumulative_scores: vec![0, 14, 0, 0],
+            }],
+        };
+        let json = write_replay(&replay);
+        let parsed = parse_replay(&json).unwrap();
+        assert_eq!(parsed.rules, replay.rules);
+        assert_eq!(parsed.rounds.len(), 1);
+        assert_eq!(parsed.rounds[0].hands[0], replay.rounds[0].hands[0]);
+        assert_eq!(parsed.rounds[0].tricks.len(), 2);
+        assert_eq!(parsed.rounds[0].tricks[1].winner, 2);
+        assert_eq!(parsed.rounds[0].cumulative_scores, vec![0, 14, 0, 0]);
+    }
+
+    #[test]
+    fn test_serialize_round_from_completed_round() {
+        let rules = hearts::RuleSet::default();
+        let seed = 99u64;
+        let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+        let (mut deck, _removed) = Deck::for_players(rules.num_players);
+        deck.shuffle(&mut rng);
+        let scores_before_round = vec![5, 10, 15, 20];
+        let mut round = hearts::Round::deal(&deck, &rules, &scores_before_round, 1);
+
+        let dealt_hands: Vec<Vec<Card>> = round.players.iter().map(|p| p.hand.clone()).collect();
+        for p in 0..rules.num_players {
+            let pass_req = hearts_ai::CardsToPassRequest {
+                rules: rules.clone(),
+                scores_before_round: scores_before_round.clone(),
+                hand: round.players[p].hand.clone(),
+                direction: round.pass_direction,
+                num_cards: 3,
+            };
+            let cards = hearts_ai::choose_cards_to_pass(&pass_req);
+            round.set_passed_cards_for_player(p, &cards);
+        }
+        round.pass_cards();
+        while !round.is_over() {
+            let card = hearts_ai::choose_card_avoid_points(&round, &mut rng);
+            round.play_card(&card).unwrap();
+        }
+        let round_points = round.points_taken();
+        let cumulative_scores: Vec<i32> = scores_before_round
+            .iter()
+            .zip(round_points.iter())
+            .map(|(&before, &taken)| before + taken)
+            .collect();
+
+        let record = round_record_from_round(&round, &cumulative_scores);
+        for p in 0..rules.num_players {
+            assert_eq!(record.hands[p].len(), dealt_hands[p].len());
+            for card in dealt_hands[p].iter() {
+                assert!(record.hands[p].contains(card));
+            }
+        }
+        assert_eq!(record.round_points, round_points);
+        assert_eq!(record.cumulative_scores, cumulative_scores);
+
+        let json = serialize_round(&round, &cumulative_scores);
+        let parsed_record: RoundRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed_record.tricks.len(), round.prev_tricks.len());
+        assert_eq!(parsed_record.cumulative_scores, cumulative_scores);
+    }
+
+    #[test]
+    fn test_round_replay_response_json() {
+        let json = round_replay_response_json(
+            r#"
+            {
+                "scores_before_round": [0, 10, 20, 30],
+                "hands": ["2C 8D AS QD", "3D 6D QS 5D", "AC KC QC 4D", "2D 9H KD AH"],
+                "pass_direction": 1,
+                "passed_cards": ["AS", "QS", "AC", "AH"],
+                "received_cards": ["AH", "AS", "QS", "AC"],
+                "tricks": [
+                    {"leader": 0, "cards": "2C AC KC QC"},
+                    {"leader": 1, "cards": "3D 6D QS 5D"}
+                ]
+            }
+        "#,
+        )
+        .unwrap();
+        let record: RoundRecord = serde_json::from_str(&json).unwrap();
+        // Trick 1 is led by seat 1 with "3D 6D QS 5D": 6D is the highest
+        // diamond (QS is off-suit and can't win), so seat 2 takes it along
+        // with the queen of spades' 13 points. No heart is ever played.
+        assert_eq!(record.round_points, vec![0, 0, 13, 0]);
+        assert_eq!(record.cumulative_scores, vec![0, 10, 33, 30]);
+        assert_eq!(record.tricks[0].winner, 1);
+    }
+
+    #[test]
+    fn test_move_log_round_trip_and_replay() {
+        let rules = hearts::RuleSet::default();
+        let seed = 12345u64;
+        let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+        let mut deck = Deck::new();
+        deck.shuffle(&mut rng);
+        let scores = vec![0; rules.num_players];
+        let mut round = hearts::Round::deal(&deck, &rules, &scores, 0);
+
+        let mut events = vec![RoundEvent::Deal {
+            seed: seed,
+            pass_direction: 0,
+        }];
+        while !round.is_over() {
+            let player = round.current_player_index();
+            let card = hearts_ai::choose_card_avoid_points(&round, &mut rng);
+            round.play_card(&card).unwrap();
+            events.push(RoundEvent::Play {
+                player: player,
+                card: card,
+            });
+            if round.current_trick.cards.is_empty() {
+                let winner = round.prev_tricks.last().unwrap().winner;
+                events.push(RoundEvent::TrickWon { winner: winner });
+            }
+        }
+        let expected_points = round.points_taken();
+
+        let json = write_move_log(&events);
+        let parsed_events = parse_move_log(&json).unwrap();
+        assert_eq!(parsed_events, events);
+
+        let replayed = replay_move_log(&rules, &parsed_events).unwrap();
+        assert!(replayed.is_over());
+        assert_eq!(replayed.points_taken(), expected_points);
+    }
+
+    #[test]
+    fn test_replay_move_log_rejects_illegal_play() {
+        let rules = hearts::RuleSet::default();
+        let events = vec![
+            RoundEvent::Deal {
+                seed: 1,
+                pass_direction: 0,
+            },
+            RoundEvent::Play {
+                player: 0,
+                card: Card::from("2H").unwrap(),
+            },
+        ];
+        assert!(replay_move_log(&rules, &events).is_err());
+    }
+
+    #[test]
+    fn test_replay_move_log_requires_leading_deal_event() {
+        let rules = hearts::RuleSet::default();
+        let events = vec![RoundEvent::Play {
+            player: 0,
+            card: Card::from("2C").unwrap(),
+        }];
+        assert!(replay_move_log(&rules, &events).is_err());
+    }
+
+    #[test]
+    fn test_cards_to_pass_response_json() {
+        let response = cards_to_pass_response_json(
+            r#"
+            {
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "AS QS JS AH 8H 2H 6D 5D 4D 3D 6C 5C 4C",
+                "direction": 1,
+                "num_cards": 3
+            }
+        "#,
+        )
+        .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(parsed["cards"], "AS QS AH");
+        assert_eq!(parsed["evaluations"].as_array().unwrap().len(), 13);
+    }
+
+    #[test]
+    fn test_card_to_play_response_json() {
+        let response = card_to_play_response_json(
+            r#"
+            {
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D AS",
+                "prev_tricks": [],
+                "current_trick": {"leader": 0, "cards": ""},
+                "pass_direction": 0,
+                "passed_cards": "",
+                "received_cards": ""
+            }
+        "#,
+            &hearts_ai::AvoidPointsChooser,
+        )
+        .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(parsed["card"], "2C");
+        assert_eq!(parsed["rollouts"], 0);
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed("{}").unwrap(), None);
+        assert_eq!(parse_seed(r#"{"seed": 42}"#).unwrap(), Some(42));
+    }
+
+    #[test]
+    fn test_card_to_play_response_json_is_deterministic_with_seed() {
+        let request = r#"
+            {
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D AS 9D 7D 6D 5D 4D 3D 9C 8C 3C TC",
+                "prev_tricks": [],
+                "current_trick": {"leader": 0, "cards": ""},
+                "pass_direction": 0,
+                "passed_cards": "",
+                "received_cards": "",
+                "seed": 7
+            }
+        "#;
+        let strategy = hearts_ai::MonteCarloChooser {
+            params: hearts_ai::MonteCarloParams {
+                num_hands: 5,
+                rollouts_per_hand: 3,
+            },
+            rollout_chooser: Box::new(hearts_ai::RandomChooser),
+        };
+        let response1 = card_to_play_response_json(request, &strategy).unwrap();
+        let response2 = card_to_play_response_json(request, &strategy).unwrap();
+        assert_eq!(response1, response2);
+    }
+
+    #[test]
+    fn test_game_session_play_without_passing() {
+        let start_response = start_game_json(r#"{"seed": 42}"#).unwrap();
+        let start: serde_json::Value = serde_json::from_str(&start_response).unwrap();
+        let game_id = start["game_id"].as_u64().unwrap();
+        assert_eq!(start["status"], "playing");
+
+        let current_player = start["current_player"].as_u64().unwrap() as usize;
+        let hand = cards_from_str(start["hands"][current_player].as_str().unwrap()).unwrap();
+        let two_of_clubs = hand
+            .iter()
+            .find(|c| c.ascii_string() == "2C")
+            .expect("dealer holds 2C");
+
+        let play_msg = format!(
+            r#"{{"game_id": {}, "player": {}, "card": "{}"}}"#,
+            game_id,
+            current_player,
+            two_of_clubs.ascii_string()
+        );
+        let play_response = play_card_json(&play_msg).unwrap();
+        let state: serde_json::Value = serde_json::from_str(&play_response).unwrap();
+        assert_eq!(state["status"], "playing");
+        assert_eq!(state["current_trick_cards"], "2C");
+    }
+
+    #[test]
+    fn test_reject_wrong_length_scores() {
+        let result = parse_cards_to_pass_request(
+            r#"
+            {
+                "scores_before_round": [30, 10, 20],
+                "hand": "2C 8D AS QD",
+                "direction": 1,
+                "num_cards": 3
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reject_duplicate_card_in_hand() {
+        let result = parse_cards_to_pass_request(
+            r#"
+            {
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D 2C QD",
+                "direction": 1,
+                "num_cards": 3
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reject_duplicate_card_across_hand_and_tricks() {
+        let result = parse_card_to_play_request(
+            r#"
+            {
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D AS",
+                "prev_tricks": [{"leader": 0, "cards": "2C 3C 4C 5C"}],
+                "current_trick": {"leader": 0, "cards": ""},
+                "pass_direction": 0,
+                "passed_cards": "",
+                "received_cards": ""
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reject_illegal_trick_leader() {
+        let result = parse_card_to_play_request(
+            r#"
+            {
+                "scores_before_round": [0, 0, 0, 0],
+                "hand": "2C 8D AS",
+                "prev_tricks": [],
+                "current_trick": {"leader": 9, "cards": ""},
+                "pass_direction": 0,
+                "passed_cards": "",
+                "received_cards": ""
+            }
+        "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_game_session_unknown_game_id() {
+        let result = play_card_json(r#"{"game_id": 999999, "player": 0, "card": "2C"}"#);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,146 @@
+use crate::card::*;
+use crate::hearts;
+use crate::hearts_ai;
+use crate::hearts_ai::{CardChooser, CardsToPassRequest};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::{Duration, Instant};
+
+// Aggregate results from playing `num_games` complete matches with a fixed
+// strategy per seat. Lets developers compare AI strategies/parameters by
+// running thousands of games instead of eyeballing a single interactive one.
+#[derive(Debug, Clone)]
+pub struct SimulationSummary {
+    pub num_games: u32,
+    pub mean_final_score: Vec<f64>,
+    pub median_final_score: Vec<f64>,
+    // Indexed by seat; a tied match increments every tied seat's counter, so
+    // this can sum to more than `num_games`.
+    pub match_wins: Vec<u32>,
+    pub moon_shots: Vec<u32>,
+    pub mean_points_per_round: f64,
+    pub elapsed: Duration,
+}
+
+// Plays `num_games` complete matches to `rules.point_limit`, one seat per
+// entry in `strategies`, with no stdin/stdout interaction. Game `i` is seeded
+// from `base_seed + i`, so a run (and comparisons between runs after tweaking
+// a strategy) is reproducible.
+pub fn simulate_matches(
+    rules: &hearts::RuleSet,
+    strategies: &[Box<dyn CardChooser>],
+    num_games: u32,
+    base_seed: u64,
+) -> SimulationSummary {
+    assert_eq!(strategies.len(), rules.num_players);
+    let start = Instant::now();
+    let num_players = rules.num_players;
+    let mut final_scores: Vec<Vec<i32>> = vec![Vec::new(); num_players];
+    let mut match_wins: Vec<u32> = vec![0; num_players];
+    let mut moon_shots: Vec<u32> = vec![0; num_players];
+    let mut total_round_points: i64 = 0;
+    let mut total_rounds: u32 = 0;
+
+    for game_index in 0..num_games {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(base_seed + (game_index as u64));
+        let (mut deck, _removed) = Deck::for_players(num_players);
+        deck.shuffle(&mut rng);
+        let mut m = hearts::Match::deal_first_round(rules, &deck);
+        loop {
+            if m.phase == hearts::MatchPhase::AwaitingPass {
+                for p in 0..num_players {
+                    let pass_req = CardsToPassRequest {
+                        rules: rules.clone(),
+                        scores_before_round: m.scores.clone(),
+                        hand: m.round.players[p].hand.clone(),
+                        direction: m.round.pass_direction,
+                        num_cards: 3,
+                    };
+                    let cards = hearts_ai::choose_cards_to_pass(&pass_req);
+                    m.round.set_passed_cards_for_player(p, &cards);
+                }
+                m.pass_cards();
+            }
+            while m.phase == hearts::MatchPhase::Playing {
+                let strategy = strategies[m.round.current_player_index()].as_ref();
+                let card = hearts_ai::choose_card(&m.round, strategy, &mut rng);
+                m.play_card(&card).expect("AI chose an illegal card");
+            }
+            total_round_points += m.round.points_taken().iter().map(|&p| p as i64).sum::<i64>();
+            total_rounds += 1;
+            if let Some(shooter) = hearts::moon_shooter_for_tricks(&m.round.prev_tricks, rules) {
+                moon_shots[shooter] += 1;
+            }
+            if m.phase == hearts::MatchPhase::MatchComplete {
+                break;
+            }
+            deck.shuffle(&mut rng);
+            m.start_next_round(&deck);
+        }
+        for p in 0..num_players {
+            final_scores[p].push(m.scores[p]);
+        }
+        for &w in m.winners().iter() {
+            match_wins[w] += 1;
+        }
+    }
+
+    let mean_final_score = final_scores
+        .iter()
+        .map(|scores| (scores.iter().sum::<i32>() as f64) / (num_games as f64))
+        .collect();
+    let median_final_score = final_scores.iter().map(|scores| median(scores)).collect();
+    let mean_points_per_round = (total_round_points as f64) / (total_rounds as f64);
+
+    return SimulationSummary {
+        num_games: num_games,
+        mean_final_score: mean_final_score,
+        median_final_score: median_final_score,
+        match_wins: match_wins,
+        moon_shots: moon_shots,
+        mean_points_per_round: mean_points_per_round,
+        elapsed: start.elapsed(),
+    };
+}
+
+fn median(values: &[i32]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort();
+    let n = sorted.len();
+    if n % 2 == 0 {
+        return ((sorted[n / 2 - 1] + sorted[n / 2]) as f64) / 2.0;
+    }
+    return sorted[n / 2] as f64;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_median_even_and_odd() {
+        assert_eq!(median(&[1, 2, 3, 4]), 2.5);
+        assert_eq!(median(&[5, 1, 3]), 3.0);
+    }
+
+    #[test]
+    fn test_simulate_matches_reports_one_entry_per_seat() {
+        let mut rules = hearts::RuleSet::default();
+        rules.point_limit = 30;
+        let strategies: Vec<Box<dyn CardChooser>> = vec![
+            Box::new(hearts_ai::AvoidPointsChooser),
+            Box::new(hearts_ai::AvoidPointsChooser),
+            Box::new(hearts_ai::AvoidPointsChooser),
+            Box::new(hearts_ai::AvoidPointsChooser),
+        ];
+        let summary = simulate_matches(&rules, &strategies, 3, 42);
+        assert_eq!(summary.num_games, 3);
+        assert_eq!(summary.mean_final_score.len(), 4);
+        assert_eq!(summary.median_final_score.len(), 4);
+        assert_eq!(summary.match_wins.len(), 4);
+        assert_eq!(summary.moon_shots.len(), 4);
+        assert!(summary.match_wins.iter().sum::<u32>() >= 3);
+        assert!(summary.mean_points_per_round > 0.0);
+    }
+}
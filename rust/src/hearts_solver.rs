@@ -0,0 +1,398 @@
+use crate::card::*;
+use crate::hand_mask::HandMask;
+use crate::hand_mask::HEARTS;
+use crate::hearts;
+use crate::hearts::RuleSet;
+
+use rand::rngs::StdRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+// A fully-visible position: every hand, the trick in progress, and the bits
+// of rules-dependent state (`hearts_broken`, `is_first_trick`) that
+// `hearts::legal_plays` would otherwise derive from the full `prev_tricks`
+// history. Tracking them directly instead of replaying history keeps each
+// search node O(num_players) to construct.
+#[derive(Debug, Clone)]
+pub struct EndgameState {
+    pub hands: Vec<HandMask>,
+    pub current_trick: Vec<Card>,
+    pub trick_leader: usize,
+    pub hearts_broken: bool,
+    pub is_first_trick: bool,
+}
+
+impl EndgameState {
+    pub fn current_player_index(&self) -> usize {
+        return (self.trick_leader + self.current_trick.len()) % self.hands.len();
+    }
+}
+
+// The legal plays for `hand` given the search-node state, as a mask. This
+// mirrors `hearts::legal_plays`, but works entirely in bitboard terms and
+// takes `hearts_broken`/`is_first_trick` directly rather than recomputing
+// them from `prev_tricks`.
+fn legal_plays_mask(
+    hand: HandMask,
+    current_trick: &[Card],
+    is_first_trick: bool,
+    hearts_broken: bool,
+    rules: &RuleSet,
+) -> HandMask {
+    if is_first_trick && current_trick.is_empty() {
+        if hand.contains(&hearts::TWO_OF_CLUBS) {
+            return HandMask::from_cards(&[hearts::TWO_OF_CLUBS]);
+        }
+        return HandMask::new();
+    }
+    if !current_trick.is_empty() {
+        let lead = current_trick[0].suit;
+        let follow = hand.in_suit(lead);
+        if !follow.is_empty() {
+            return follow;
+        }
+        if is_first_trick && !rules.points_on_first_trick {
+            let non_points = non_point_cards(hand, rules);
+            if !non_points.is_empty() {
+                return non_points;
+            }
+        }
+        return hand;
+    }
+    // Leading a new trick: no hearts unless they're broken or it's all we have.
+    if !hearts_broken {
+        let non_hearts = HandMask(hand.0 & !HEARTS);
+        if !non_hearts.is_empty() {
+            return non_hearts;
+        }
+    }
+    return hand;
+}
+
+fn non_point_cards(hand: HandMask, rules: &RuleSet) -> HandMask {
+    let mut result = HandMask::new();
+    for c in hand.to_cards().iter() {
+        if hearts::points_for_card(c, rules) <= 0 {
+            result.insert(c);
+        }
+    }
+    return result;
+}
+
+fn card_index(card: &Card) -> usize {
+    return (card.suit as usize) * 13 + ((card.rank.value as usize) - 2);
+}
+
+fn random_keys(n: usize, rng: &mut impl RngCore) -> Vec<u64> {
+    return (0..n).map(|_| rng.next_u64()).collect();
+}
+
+// Random keys for the Zobrist hash of an `EndgameState`. A state's hash is
+// the XOR of: a `card_holder` key for every (card, player) pair where that
+// player still holds the card, a `trick_card` key for every (card, position)
+// already played to the trick in progress, a `leader` key for the current
+// trick's leader, and `hearts_broken`/`first_trick` keys when those flags are
+// set. XORing the relevant keys in and out as cards move is O(1) per move,
+// so `Solver::play` never has to rehash a whole state from scratch.
+struct ZobristKeys {
+    card_holder: Vec<u64>, // [holder * 52 + card_index]
+    trick_card: Vec<u64>,  // [position * 52 + card_index]
+    leader: Vec<u64>,      // [leader]
+    hearts_broken: u64,
+    first_trick: u64,
+}
+
+impl ZobristKeys {
+    fn new(num_players: usize, rng: &mut impl RngCore) -> ZobristKeys {
+        let card_holder = random_keys(52 * num_players, rng);
+        let trick_card = random_keys(52 * num_players, rng);
+        let leader = random_keys(num_players, rng);
+        return ZobristKeys {
+            card_holder,
+            trick_card,
+            leader,
+            hearts_broken: rng.next_u64(),
+            first_trick: rng.next_u64(),
+        };
+    }
+
+    fn card_holder_key(&self, holder: usize, card: &Card) -> u64 {
+        return self.card_holder[holder * 52 + card_index(card)];
+    }
+
+    fn trick_card_key(&self, position: usize, card: &Card) -> u64 {
+        return self.trick_card[position * 52 + card_index(card)];
+    }
+
+    fn hash(&self, state: &EndgameState) -> u64 {
+        let mut h = self.leader[state.trick_leader];
+        if state.hearts_broken {
+            h ^= self.hearts_broken;
+        }
+        if state.is_first_trick {
+            h ^= self.first_trick;
+        }
+        for (holder, hand) in state.hands.iter().enumerate() {
+            for c in hand.to_cards().iter() {
+                h ^= self.card_holder_key(holder, c);
+            }
+        }
+        for (position, c) in state.current_trick.iter().enumerate() {
+            h ^= self.trick_card_key(position, c);
+        }
+        return h;
+    }
+}
+
+// The points each player still has left to take from the tricks yet to be
+// played, before any moon-shot adjustment (see `hearts::points_for_tricks`);
+// a caller who wants a moon-shot-adjusted total has to add these to the
+// points already banked in `prev_tricks` and re-run that check over the
+// combined trick list.
+type PointDelta = Vec<i32>;
+
+// A perfect-information (double-dummy) Hearts solver: depth-first search
+// over remaining trick play, transposition-cached by Zobrist hash. At each
+// node the player to move tries every legal play and keeps whichever leaves
+// them with the lowest final point total — each player plays to minimize
+// their own score, not to minimize a shared "declarer" total, since Hearts
+// has no partnership to optimize for.
+pub struct Solver {
+    rules: RuleSet,
+    keys: ZobristKeys,
+    table: HashMap<u64, PointDelta>,
+}
+
+impl Solver {
+    pub fn new(rules: &RuleSet) -> Solver {
+        let mut rng = StdRng::seed_from_u64(0x5ea27575);
+        return Solver {
+            rules: rules.clone(),
+            keys: ZobristKeys::new(rules.num_players, &mut rng),
+            table: HashMap::new(),
+        };
+    }
+
+    pub fn transposition_table_len(&self) -> usize {
+        return self.table.len();
+    }
+
+    // Solves `state` exactly, returning the points each player takes on the
+    // remaining tricks.
+    pub fn solve(&mut self, state: &EndgameState) -> PointDelta {
+        let hash = self.keys.hash(state);
+        return self.solve_hash(state, hash);
+    }
+
+    fn solve_hash(&mut self, state: &EndgameState, hash: u64) -> PointDelta {
+        if let Some(cached) = self.table.get(&hash) {
+            return cached.clone();
+        }
+        let result = self.solve_uncached(state, hash);
+        self.table.insert(hash, result.clone());
+        return result;
+    }
+
+    fn solve_uncached(&mut self, state: &EndgameState, hash: u64) -> PointDelta {
+        let num_players = state.hands.len();
+        if state.hands.iter().all(|h| h.is_empty()) {
+            return vec![0; num_players];
+        }
+        let mover = state.current_player_index();
+        let legal = legal_plays_mask(
+            state.hands[mover],
+            &state.current_trick,
+            state.is_first_trick,
+            state.hearts_broken,
+            &self.rules,
+        );
+        let mut best: Option<PointDelta> = None;
+        for card in legal.to_cards().iter() {
+            let (next_state, next_hash, resolved_trick) = self.play(state, hash, mover, card);
+            let mut value = self.solve_hash(&next_state, next_hash);
+            if let Some((winner, trick_points)) = resolved_trick {
+                value[winner] += trick_points;
+            }
+            if best.is_none() || value[mover] < best.as_ref().unwrap()[mover] {
+                best = Some(value);
+            }
+        }
+        return best.expect("current player has no legal plays");
+    }
+
+    // Applies `mover` playing `card` from `state` (whose Zobrist hash is
+    // `hash`), returning the resulting state, its hash, and -- if this play
+    // completed a trick -- the winner and the points that trick is worth, so
+    // the caller can credit them once the subtree's result comes back.
+    fn play(
+        &self,
+        state: &EndgameState,
+        hash: u64,
+        mover: usize,
+        card: &Card,
+    ) -> (EndgameState, u64, Option<(usize, i32)>) {
+        let num_players = state.hands.len();
+        let mut hands = state.hands.clone();
+        hands[mover].remove(card);
+        let mut h = hash ^ self.keys.card_holder_key(mover, card);
+
+        let position = state.current_trick.len();
+        h ^= self.keys.trick_card_key(position, card);
+        let mut current_trick = state.current_trick.clone();
+        current_trick.push(*card);
+
+        if current_trick.len() < num_players {
+            let next_state = EndgameState {
+                hands,
+                current_trick,
+                trick_leader: state.trick_leader,
+                hearts_broken: state.hearts_broken,
+                is_first_trick: state.is_first_trick,
+            };
+            return (next_state, h, None);
+        }
+
+        // The trick is complete: resolve the winner, fold the points into
+        // the result the caller will add in, and clear the transient
+        // per-trick keys before hashing the next (empty-trick) state.
+        let winner_offset = hearts::trick_winner_index(&current_trick);
+        let winner = (state.trick_leader + winner_offset) % num_players;
+        let trick_points = hearts::points_for_cards(&current_trick, &self.rules);
+        for (i, c) in current_trick.iter().enumerate() {
+            h ^= self.keys.trick_card_key(i, c);
+        }
+        h ^= self.keys.leader[state.trick_leader];
+        h ^= self.keys.leader[winner];
+        let hearts_broken = state.hearts_broken
+            || current_trick.iter().any(|c| {
+                c.suit == Suit::Hearts
+                    || (self.rules.queen_breaks_hearts && *c == hearts::QUEEN_OF_SPADES)
+            });
+        if hearts_broken != state.hearts_broken {
+            h ^= self.keys.hearts_broken;
+        }
+        if state.is_first_trick {
+            h ^= self.keys.first_trick;
+        }
+        let next_state = EndgameState {
+            hands,
+            current_trick: Vec::new(),
+            trick_leader: winner,
+            hearts_broken,
+            is_first_trick: false,
+        };
+        return (next_state, h, Some((winner, trick_points)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn c(s: &str) -> Vec<Card> {
+        cards_from_str(s).unwrap()
+    }
+
+    fn mask(s: &str) -> HandMask {
+        HandMask::from_cards(&c(s))
+    }
+
+    fn state(hands: Vec<&str>, trick: &str, leader: usize, hearts_broken: bool) -> EndgameState {
+        return EndgameState {
+            hands: hands.into_iter().map(mask).collect(),
+            current_trick: c(trick),
+            trick_leader: leader,
+            hearts_broken,
+            is_first_trick: false,
+        };
+    }
+
+    #[test]
+    fn test_no_hearts_led_until_broken() {
+        let rules = RuleSet::default();
+        let legal = legal_plays_mask(mask("4H 5C"), &[], false, false, &rules);
+        assert_eq!(legal, mask("5C"));
+    }
+
+    #[test]
+    fn test_hearts_allowed_to_lead_once_broken() {
+        let rules = RuleSet::default();
+        let hand = mask("4H 5C");
+        assert_eq!(legal_plays_mask(hand, &[], false, true, &rules), hand);
+    }
+
+    #[test]
+    fn test_first_trick_must_open_two_of_clubs() {
+        let rules = RuleSet::default();
+        let legal = legal_plays_mask(mask("2C AS 4H"), &[], true, false, &rules);
+        assert_eq!(legal, mask("2C"));
+    }
+
+    #[test]
+    fn test_first_trick_avoids_points_unless_forced() {
+        let rules = RuleSet::default();
+        // Void in the led suit (clubs) on the first trick, holding one
+        // harmless diamond and one costly heart: the diamond is preferred.
+        let legal = legal_plays_mask(mask("4D 5H"), &c("2C"), true, false, &rules);
+        assert_eq!(legal, mask("4D"));
+    }
+
+    #[test]
+    fn test_first_trick_allows_points_when_no_alternative() {
+        let rules = RuleSet::default();
+        // Void in clubs and holding nothing but hearts: forced to play one.
+        let legal = legal_plays_mask(mask("4H 5H"), &c("2C"), true, false, &rules);
+        assert_eq!(legal, mask("4H 5H"));
+    }
+
+    #[test]
+    fn test_single_trick_each_player_must_follow() {
+        let rules = RuleSet::default();
+        let mut solver = Solver::new(&rules);
+        // Everyone has exactly one card left and no choices: clubs led, and
+        // whoever's forced to win the trick (the high club) takes the queen
+        // along with it even though they never touched it.
+        let s = state(vec!["3C", "2C", "QS", "9C"], "", 0, true);
+        let result = solver.solve(&s);
+        assert_eq!(result, vec![0, 0, 0, 13]);
+    }
+
+    #[test]
+    fn test_ducks_under_the_trick_to_avoid_the_queen() {
+        let rules = RuleSet::default();
+        let mut solver = Solver::new(&rules);
+        // Clubs led; P0/P1/P2 already played 2C/5H/9C, so P3 is in with both
+        // the jack and the trey of clubs. Winning with the jack also wins
+        // the next (forced) trick, which carries the queen -- ducking under
+        // with the trey instead leaves P3 clean.
+        let s = state(vec!["AS", "3H", "QS", "JC 3C"], "2C 5H 9C", 0, true);
+        let result = solver.solve(&s);
+        assert_eq!(result, vec![14, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_transposition_table_is_populated_and_reused() {
+        let rules = RuleSet::default();
+        let mut solver = Solver::new(&rules);
+        let s = state(vec!["AS 2H", "3H 4S", "5S QS", "6H 7S"], "", 0, true);
+        solver.solve(&s);
+        let populated = solver.transposition_table_len();
+        assert!(populated > 0);
+        solver.solve(&s);
+        // Solving the same position again can't add new entries.
+        assert_eq!(solver.transposition_table_len(), populated);
+    }
+
+    #[test]
+    fn test_mid_trick_state_is_solved_from_the_next_player() {
+        let rules = RuleSet::default();
+        let mut solver = Solver::new(&rules);
+        // P0 already led the ace of spades (and has no cards left); P1, P2
+        // and P3 are all forced to follow with their one remaining card
+        // each, and the ace can't be beaten.
+        let s = state(vec!["", "QS", "KS", "2H"], "AS", 0, true);
+        let result = solver.solve(&s);
+        assert_eq!(result, vec![14, 0, 0, 0]);
+    }
+}
@@ -1,6 +1,9 @@
 mod card;
 mod hearts;
 mod hearts_ai;
+mod hand_mask;
+mod hearts_solver;
+mod transcript;
 
 use std::io;
 
@@ -14,8 +17,9 @@ use hearts_ai::MonteCarloParams;
 use hearts_ai::ChooseCardStrategy;
 
 // TODO:
-// pass cards
-// match with multiple rounds to 100 points
+// This file predates the CardChooser/ChooseCardToPlayRequest API used
+// elsewhere and needs to be ported over; main_console.rs is the up to date
+// console entry point, including the multi-round hearts::Match loop.
 
 fn run_ai_simulation() {
     let mut deck = Deck::new();
@@ -1,6 +1,9 @@
 mod card;
 mod hearts;
 mod hearts_ai;
+mod hand_mask;
+mod hearts_solver;
+mod transcript;
 mod hearts_json;
 
 use std::ffi::CStr;
@@ -9,12 +12,14 @@ use std::io::Read;
 use std::ptr;
 use std::slice;
 
+use rand::rngs::StdRng;
 use rand::thread_rng;
 use rand::Rng;
+use rand::SeedableRng;
 
 use card::*;
 use hearts_ai::MonteCarloParams;
-use hearts_ai::{CardToPlayRequest, CardToPlayStrategy, CardsToPassRequest};
+use hearts_ai::{CardToPlayDirectRequest, CardsToPassRequest, MixedRandomAvoidPointsChooser, MonteCarloChooser};
 
 /* Example: paste to stdin:
 {
@@ -32,13 +37,13 @@ fn main() {
     let mut buffer = String::new();
     std::io::stdin().read_to_string(&mut buffer);
     let req = hearts_json::parse_card_to_play_request(&buffer).unwrap();
-    let ai_strat = CardToPlayStrategy::MonteCarloMixedRandomAvoidPoints(
-        0.1,
-        MonteCarloParams {
+    let ai_strat = MonteCarloChooser {
+        params: MonteCarloParams {
             num_hands: 50,
             rollouts_per_hand: 20,
         },
-    );
+        rollout_chooser: Box::new(MixedRandomAvoidPointsChooser { p_random: 0.1 }),
+    };
     let ai_card = hearts_ai::choose_card(&req, &ai_strat, &mut rng);
     println!("{}", ai_card.symbol_string());
 }
@@ -54,9 +59,10 @@ fn cards_to_pass_req_from_json(s: *const u8, len: u32) -> CardsToPassRequest {
     return hearts_json::parse_cards_to_pass_request(&r_str).unwrap();
 }
 
-fn card_to_play_req_from_json(s: *const u8, len: u32) -> CardToPlayRequest {
+fn card_to_play_req_from_json(s: *const u8, len: u32) -> (CardToPlayDirectRequest, String) {
     let r_str = string_from_ptr(s, len);
-    return hearts_json::parse_card_to_play_request(&r_str).unwrap();
+    let req = hearts_json::parse_card_to_play_request(&r_str).unwrap();
+    return (req, r_str);
 }
 
 // Parses `len` bytes of `s` as a JSON-encoded CardsToPassRequest.
@@ -83,21 +89,29 @@ pub extern "C" fn cards_to_pass_from_json(s: *const u8, len: u32, pass_out: *mut
     }
 }
 
-// Parses `len` bytes of `s` as a JSON-encoded CardToPlayRequest.
+// Parses `len` bytes of `s` as a JSON-encoded CardToPlayRequest. An optional
+// top-level "strategy" object (see hearts_json::parse_strategy) selects the
+// AI's strategy and strength; if omitted, this defaults to the same
+// Monte-Carlo-mixed-random strategy this function always used to hardcode.
+// If the request has a "seed" field, the Monte Carlo rollouts are driven by a
+// StdRng seeded from it instead of thread_rng(), for reproducible decisions.
 // Returns the best card to play as an index into the "hand" field of the request.
 // See ffi_test.py for an example of how to call.
 #[no_mangle]
 pub extern "C" fn card_to_play_from_json(s: *const u8, len: u32) -> i32 {
-    let req = unsafe { card_to_play_req_from_json(s, len) };
-    let ai_strat = CardToPlayStrategy::MonteCarloMixedRandomAvoidPoints(
-        0.1,
-        MonteCarloParams {
-            num_hands: 50,
-            rollouts_per_hand: 20,
-        },
-    );
-    let mut rng = thread_rng();
-    let ai_card = hearts_ai::choose_card(&req, &ai_strat, &mut rng);
+    let (req, r_str) = unsafe { card_to_play_req_from_json(s, len) };
+    let ai_strat = hearts_json::parse_strategy(&r_str).unwrap();
+    let seed = hearts_json::parse_seed(&r_str).unwrap();
+    let ai_card = match seed {
+        Some(seed) => {
+            let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+            hearts_ai::choose_card(&req, ai_strat.as_ref(), &mut rng)
+        }
+        None => {
+            let mut rng = thread_rng();
+            hearts_ai::choose_card(&req, ai_strat.as_ref(), &mut rng)
+        }
+    };
     return match req.hand.iter().position(|&c| c == ai_card) {
         Some(i) => i as i32,
         None => -1,
@@ -112,7 +126,7 @@ pub extern "C" fn card_to_play_from_json(s: *const u8, len: u32) -> i32 {
 // See ffi_test.py for an example of how to call.
 #[no_mangle]
 pub extern "C" fn legal_plays_from_json(s: *const u8, len: u32, legal_out: *mut u8, out_len: u32) {
-    let req = unsafe { card_to_play_req_from_json(s, len) };
+    let (req, _r_str) = unsafe { card_to_play_req_from_json(s, len) };
     let legal_plays = req.legal_plays();
     if req.hand.len() > (out_len as usize) {
         panic!(
@@ -129,6 +143,29 @@ pub extern "C" fn legal_plays_from_json(s: *const u8, len: u32, legal_out: *mut
     }
 }
 
+// Parses `len` bytes of `s` as a JSON-encoded RoundResultRequest (the hands
+// as dealt, the pass, and every trick of a round a caller just finished
+// driving through this API) and writes the resulting replay JSON --- the
+// same shape `write_replay` uses for each entry of GameReplay.rounds --- to
+// `out`. Returns the number of bytes written, or -1 if `out_len` is too
+// small to hold the result.
+// See ffi_test.py for an example of how to call.
+#[no_mangle]
+pub extern "C" fn round_replay_to_json(s: *const u8, len: u32, out: *mut u8, out_len: u32) -> i32 {
+    let r_str = string_from_ptr(s, len);
+    let json = hearts_json::round_replay_response_json(&r_str).unwrap();
+    let bytes = json.as_bytes();
+    if bytes.len() > (out_len as usize) {
+        return -1;
+    }
+    for (i, &b) in bytes.iter().enumerate() {
+        unsafe {
+            std::ptr::write_unaligned(out.offset(i as isize), b);
+        }
+    }
+    return bytes.len() as i32;
+}
+
 // Parses `len` bytes of `s` as a JSON-encoded trick history.
 // Writes the points taken by each player to `points_out`, whose size must be
 // at least the number of players.
@@ -1,6 +1,9 @@
 mod card;
 mod hearts;
 mod hearts_ai;
+mod hand_mask;
+mod hearts_solver;
+mod transcript;
 
 use std::io;
 
@@ -11,86 +14,72 @@ use rand::rngs::StdRng;
 
 use card::*;
 use hearts_ai::MonteCarloParams;
-use hearts_ai::CardToPlayStrategy;
+use hearts_ai::{CardChooser, MixedRandomAvoidPointsChooser, MonteCarloChooser};
 
-// TODO:
-// pass cards
-// match with multiple rounds to 100 points
+fn print_trick_winner(winner: usize) {
+    if winner == 0 {
+        println!("You take the trick");
+    }
+    else {
+        println!("P{} takes the trick", winner);
+    }
+    println!("==================");
+}
 
-fn main() {
-    let mut deck = Deck::new();
-    let mut rng = thread_rng();
-    let rules = hearts::RuleSet::default();
-    let ai_strat = CardToPlayStrategy::MonteCarloMixedRandomAvoidPoints(
-        0.1, MonteCarloParams {num_hands: 50, rollouts_per_hand: 20});
-    deck.shuffle(&mut rng);
-    let pass_dir = 1u32;
-    let mut round = hearts::Round::deal(&deck, &rules, pass_dir);
-    println!("Your hand: {}", all_suit_groups(&round.players[0].hand));
-    if pass_dir > 0 {
-        let mut passed = false;
-        while !passed {
-            println!("Enter 3 cards to pass:");
-            let mut input = String::new();
-            if io::stdin().read_line(&mut input).is_ok() {
-                match cards_from_str(&input) {
-                    Ok(cards) => {
-                        if round.can_pass_cards(0, &cards) {
-                            round.set_passed_cards_for_player(0, &cards);
-                            passed = true;
-                        }
-                        else {
-                            println!("Cannot pass those cards");
-                        }
+fn do_pass(m: &mut hearts::Match, rules: &hearts::RuleSet, ai_strat: &dyn CardChooser) {
+    let mut passed = false;
+    while !passed {
+        println!("Enter 3 cards to pass:");
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_ok() {
+            match cards_from_str(&input) {
+                Ok(cards) => {
+                    if m.round.can_pass_cards(0, &cards) {
+                        m.round.set_passed_cards_for_player(0, &cards);
+                        passed = true;
                     }
-                    Err(error) => {
-                        println!("Invalid input");
+                    else {
+                        println!("Cannot pass those cards");
                     }
-                };
-            }
-        }
-        for i in 1..round.players.len() {
-            let pass_req = hearts_ai::CardsToPassRequest {
-                rules: rules.clone(),
-                hand: round.players[i].hand.clone(),
-                direction: pass_dir,
-                num_cards: 3,
+                }
+                Err(error) => {
+                    println!("Invalid input");
+                }
             };
-            let cards = hearts_ai::choose_cards_to_pass(&pass_req);
-            // println!("P{} passes {}", i, symbol_str_from_cards(&cards));
-            round.set_passed_cards_for_player(i, &cards);
         }
-        round.pass_cards();
-        println!("You received: {}", symbol_str_from_cards(&round.players[0].received_cards));
-        println!("Your hand: {}", all_suit_groups(&round.players[0].hand));
     }
-
-
-    fn print_trick_winner(winner: usize) {
-        if winner == 0 {
-            println!("You take the trick");
-        }
-        else {
-            println!("P{} takes the trick", winner);
-        }
-        println!("==================");
+    for i in 1..m.round.players.len() {
+        let pass_req = hearts_ai::CardsToPassRequest {
+            rules: rules.clone(),
+            scores_before_round: m.scores.clone(),
+            hand: m.round.players[i].hand.clone(),
+            direction: m.round.pass_direction,
+            num_cards: 3,
+        };
+        let cards = hearts_ai::choose_cards_to_pass(&pass_req);
+        m.round.set_passed_cards_for_player(i, &cards);
     }
+    m.pass_cards();
+    println!("You received: {}", symbol_str_from_cards(&m.round.players[0].received_cards));
+    println!("Your hand: {}", all_suit_groups(&m.round.players[0].hand));
+}
 
-    while !round.is_over() {
+fn do_play(m: &mut hearts::Match, ai_strat: &dyn CardChooser, rng: &mut impl Rng) {
+    while m.phase == hearts::MatchPhase::Playing {
         let ai_card = hearts_ai::choose_card(
-            &hearts_ai::CardToPlayRequest::from_round(&round), &ai_strat, &mut rng);
-        if round.current_player_index() == 0 {
+            &m.round, ai_strat, rng);
+        if m.round.current_player_index() == 0 {
             println!("Choose a card (AI: {}): {}",
-                ai_card.symbol_string(), all_suit_groups(&round.players[0].hand));
+                ai_card.symbol_string(), all_suit_groups(&m.round.players[0].hand));
             let mut input = String::new();
             if io::stdin().read_line(&mut input).is_ok() {
                 match Card::from(&input.trim()) {
                     Ok(card) => {
-                        if round.legal_plays().contains(&card) {
-                            round.play_card(&card).expect("");
+                        if m.round.legal_plays().contains(&card) {
+                            m.play_card(&card).expect("");
                             println!("You played {}", card.symbol_string());
-                            if round.current_trick.cards.is_empty() {
-                                let t = round.prev_tricks.last().expect("");
+                            if m.round.current_trick.cards.is_empty() {
+                                let t = m.round.prev_tricks.last().expect("");
                                 print_trick_winner(t.winner);
                             }
                         }
@@ -105,14 +94,49 @@ fn main() {
             }
         }
         else {
-            println!("P{} plays {}", round.current_player_index(), ai_card.symbol_string());
-            round.play_card(&ai_card).expect("");
-            if round.current_trick.cards.is_empty() {
-                let t = round.prev_tricks.last().expect("");
+            println!("P{} plays {}", m.round.current_player_index(), ai_card.symbol_string());
+            m.play_card(&ai_card).expect("");
+            if m.round.current_trick.cards.is_empty() {
+                let t = m.round.prev_tricks.last().expect("");
                 print_trick_winner(t.winner);
             }
         }
     }
-    let points = round.points_taken();
-    println!("Score: {:?}", points);
+}
+
+fn main() {
+    let rules = hearts::RuleSet::default();
+    let (mut deck, _removed) = Deck::for_players(rules.num_players);
+    let mut rng = thread_rng();
+    let ai_strat = MonteCarloChooser {
+        params: MonteCarloParams {num_hands: 50, rollouts_per_hand: 20},
+        rollout_chooser: Box::new(MixedRandomAvoidPointsChooser { p_random: 0.1 }),
+    };
+    deck.shuffle(&mut rng);
+    let mut m = hearts::Match::deal_first_round(&rules, &deck);
+
+    loop {
+        println!("Your hand: {}", all_suit_groups(&m.round.players[0].hand));
+        if m.phase == hearts::MatchPhase::AwaitingPass {
+            do_pass(&mut m, &rules, &ai_strat);
+        }
+        do_play(&mut m, &ai_strat, &mut rng);
+
+        let points = m.round.points_taken();
+        println!("Round score: {:?}", points);
+        println!("Match score: {:?}", m.scores);
+
+        if m.phase == hearts::MatchPhase::MatchComplete {
+            let winners = m.winners();
+            if winners.len() == 1 {
+                println!("P{} wins the match", winners[0]);
+            }
+            else {
+                println!("Tie between players {:?}", winners);
+            }
+            break;
+        }
+        deck.shuffle(&mut rng);
+        m.start_next_round(&deck);
+    }
 }
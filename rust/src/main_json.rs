@@ -1,6 +1,9 @@
 mod card;
 mod hearts;
 mod hearts_ai;
+mod hand_mask;
+mod hearts_solver;
+mod transcript;
 mod hearts_json;
 
 use std::ffi::CStr;
@@ -11,10 +14,12 @@ use std::ptr;
 
 use rand::Rng;
 use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 
 use card::*;
 use hearts_ai::MonteCarloParams;
-use hearts_ai::{CardToPlayRequest, CardToPlayStrategy};
+use hearts_ai::{CardToPlayDirectRequest, CardsToPassRequest, MixedRandomAvoidPointsChooser, MonteCarloChooser};
 
 /* Example: paste to stdin:
 {
@@ -29,29 +34,56 @@ fn main() {
     let mut buffer = String::new();
     std::io::stdin().read_to_string(&mut buffer);
     let req = hearts_json::parse_card_to_play_request(&buffer).unwrap();
-    let ai_strat = CardToPlayStrategy::MonteCarloMixedRandomAvoidPoints(
-        0.1, MonteCarloParams {num_hands: 50, rollouts_per_hand: 20});
+    let ai_strat = MonteCarloChooser {
+        params: MonteCarloParams {num_hands: 50, rollouts_per_hand: 20},
+        rollout_chooser: Box::new(MixedRandomAvoidPointsChooser { p_random: 0.1 }),
+    };
     let ai_card = hearts_ai::choose_card(&req, &ai_strat, &mut rng);
     println!("{}", ai_card.symbol_string());
 }
 
-unsafe fn card_to_play_req_from_json(s: *const u8, len: u32) -> CardToPlayRequest {
+fn string_from_ptr(s: *const u8, len: u32) -> String {
     assert!(!s.is_null());
-    let bytes = unsafe {slice::from_raw_parts(s, len as usize)};
-    let r_str = String::from_utf8(bytes.to_vec()).unwrap();
-    return hearts_json::parse_card_to_play_request(&r_str).unwrap();
+    let bytes = unsafe { slice::from_raw_parts(s, len as usize) };
+    return String::from_utf8(bytes.to_vec()).unwrap();
 }
 
-// Parses `len` bytes of `s` as a JSON-encoded CardToPlayRequest.
-// Returns the best card to play as an index into the "hand" field of the request.
+fn card_to_play_req_from_json(s: *const u8, len: u32) -> (CardToPlayDirectRequest, String) {
+    let r_str = string_from_ptr(s, len);
+    let req = hearts_json::parse_card_to_play_request(&r_str).unwrap();
+    return (req, r_str);
+}
+
+fn cards_to_pass_req_from_json(s: *const u8, len: u32) -> (CardsToPassRequest, String) {
+    let r_str = string_from_ptr(s, len);
+    let req = hearts_json::parse_cards_to_pass_request(&r_str).unwrap();
+    return (req, r_str);
+}
+
+// Parses `len` bytes of `s` as a JSON-encoded CardToPlayRequest. An optional
+// top-level "strategy" object (see hearts_json::parse_strategy) selects the
+// AI's strategy and strength; if omitted, this defaults to the same
+// Monte-Carlo-mixed-random strategy this function always used to hardcode.
+// An optional top-level "seed" field drives the Monte Carlo rollouts from a
+// StdRng seeded from it instead of thread_rng(), for reproducible decisions.
+// Returns the best card to play as an index into the "hand" field of the
+// request.
 // See ffi_test.py for an example of how to call.
 #[no_mangle]
-pub extern fn card_to_play_from_json(s: *const u8, len: u32) -> i32 {
-    let req = unsafe {card_to_play_req_from_json(s, len)};
-    let ai_strat = CardToPlayStrategy::MonteCarloMixedRandomAvoidPoints(
-        0.1, MonteCarloParams {num_hands: 50, rollouts_per_hand: 20});
-    let mut rng = thread_rng();
-    let ai_card = hearts_ai::choose_card(&req, &ai_strat, &mut rng);
+pub extern "C" fn card_to_play_from_json(s: *const u8, len: u32) -> i32 {
+    let (req, r_str) = card_to_play_req_from_json(s, len);
+    let ai_strat = hearts_json::parse_strategy(&r_str).unwrap();
+    let seed = hearts_json::parse_seed(&r_str).unwrap();
+    let ai_card = match seed {
+        Some(seed) => {
+            let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+            hearts_ai::choose_card(&req, ai_strat.as_ref(), &mut rng)
+        }
+        None => {
+            let mut rng = thread_rng();
+            hearts_ai::choose_card(&req, ai_strat.as_ref(), &mut rng)
+        }
+    };
     return match req.hand.iter().position(|&c| c == ai_card) {
         Some(i) => i as i32,
         None => -1,
@@ -63,9 +95,10 @@ pub extern fn card_to_play_from_json(s: *const u8, len: u32) -> i32 {
 // card at index i in the hand writes a 1 to `legal_out[i]` if the card is legal
 // to play and writes 0 if not. The size of `legal_out` must be at least the
 // number of cards in the hand.
+// See ffi_test.py for an example of how to call.
 #[no_mangle]
-pub extern fn legal_plays_from_json(s: *const u8, len: u32, legal_out: *mut u8, out_len: u32) {
-    let req = unsafe {card_to_play_req_from_json(s, len)};
+pub extern "C" fn legal_plays_from_json(s: *const u8, len: u32, legal_out: *mut u8, out_len: u32) {
+    let (req, _) = card_to_play_req_from_json(s, len);
     let legal_plays = req.legal_plays();
     if req.hand.len() > (out_len as usize) {
         panic!("`out_len` is {} but hand has {} cards", out_len, req.hand.len());
@@ -76,4 +109,42 @@ pub extern fn legal_plays_from_json(s: *const u8, len: u32, legal_out: *mut u8,
             std::ptr::write_unaligned(legal_out.offset(i as isize), val);
         }
     }
-}
\ No newline at end of file
+}
+
+// Parses `len` bytes of `s` as a JSON-encoded CardsToPassRequest. An optional
+// top-level "strategy" object with `"kind": "random"` passes random cards
+// instead of running the danger-score heuristic in
+// hearts_ai::choose_cards_to_pass (passing has no Monte Carlo or
+// avoid-points variant to select, unlike the play strategies above).
+// Determines the best cards to pass, and writes their positions in the
+// "hand" field of the request to `indices_out`, whose size must be at least
+// "num_cards".
+// See ffi_test.py for an example of how to call.
+#[no_mangle]
+pub extern "C" fn cards_to_pass_from_json(
+    s: *const u8,
+    len: u32,
+    indices_out: *mut i32,
+    out_len: u32,
+) {
+    let (req, r_str) = cards_to_pass_req_from_json(s, len);
+    let use_random = hearts_json::parse_strategy_is_random(&r_str).unwrap();
+    let cards_to_pass = if use_random {
+        hearts_ai::choose_cards_to_pass_random(&req)
+    } else {
+        hearts_ai::choose_cards_to_pass(&req)
+    };
+    if cards_to_pass.len() > (out_len as usize) {
+        panic!(
+            "`out_len` is {} but {} cards were chosen to pass",
+            out_len,
+            cards_to_pass.len()
+        );
+    }
+    for (i, card) in cards_to_pass.iter().enumerate() {
+        let index = req.hand.iter().position(|c| c == card).unwrap() as i32;
+        unsafe {
+            std::ptr::write_unaligned(indices_out.offset(i as isize), index);
+        }
+    }
+}
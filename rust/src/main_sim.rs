@@ -0,0 +1,33 @@
+mod card;
+mod hearts;
+mod hearts_ai;
+mod hand_mask;
+mod hearts_solver;
+mod transcript;
+mod hearts_sim;
+
+use hearts_ai::{AvoidPointsChooser, CardChooser, MixedRandomAvoidPointsChooser, MonteCarloChooser, MonteCarloParams, RandomChooser};
+
+// Headless benchmark: play a batch of complete matches between fixed
+// strategies and print the aggregate result, for comparing AI tuning without
+// an interactive game.
+fn main() {
+    let rules = hearts::RuleSet::default();
+    let strategies: Vec<Box<dyn CardChooser>> = vec![
+        Box::new(AvoidPointsChooser),
+        Box::new(MonteCarloChooser {
+            params: MonteCarloParams {num_hands: 50, rollouts_per_hand: 20},
+            rollout_chooser: Box::new(MixedRandomAvoidPointsChooser { p_random: 0.1 }),
+        }),
+        Box::new(MonteCarloChooser {
+            params: MonteCarloParams {num_hands: 50, rollouts_per_hand: 20},
+            rollout_chooser: Box::new(AvoidPointsChooser),
+        }),
+        Box::new(MonteCarloChooser {
+            params: MonteCarloParams {num_hands: 50, rollouts_per_hand: 20},
+            rollout_chooser: Box::new(RandomChooser),
+        }),
+    ];
+    let summary = hearts_sim::simulate_matches(&rules, &strategies, 100, 42);
+    println!("{:#?}", summary);
+}
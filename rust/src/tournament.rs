@@ -0,0 +1,253 @@
+use crate::card::Deck;
+use crate::hearts;
+use crate::hearts_ai;
+use crate::hearts_ai::{CardChooser, CardsToPassRequest};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// The classic "12 points for 1st, split among ties" scheme: 12 for an
+// outright win, 6 each for a 2-way tie, 4 each for a 3-way tie, 3 each for a
+// 4-way tie (lower score wins, as in `RuleSet::point_limit`).
+pub fn default_victory_points(scores: &[i32]) -> Vec<u64> {
+    let best = *scores.iter().min().unwrap();
+    let num_winners = scores.iter().filter(|&&s| s == best).count();
+    return scores
+        .iter()
+        .map(|&s| if s == best { (12 / num_winners) as u64 } else { 0 })
+        .collect();
+}
+
+// Per-seat results from running a `Tournament`: mean and standard deviation
+// of final score, mean victory points, win rate (with its standard error),
+// and how often a seat shot the moon, so callers can judge whether an
+// observed gap between two strategies is significant or just noise from a
+// small `num_matches`.
+#[derive(Debug, Clone)]
+pub struct TournamentStats {
+    pub num_matches: u32,
+    pub mean_final_score: Vec<f64>,
+    pub final_score_stddev: Vec<f64>,
+    pub mean_victory_points: Vec<f64>,
+    pub win_rate: Vec<f64>,
+    pub win_rate_stderr: Vec<f64>,
+    pub moon_shot_rate: Vec<f64>,
+}
+
+// Configuration for a batch of complete matches between fixed strategies,
+// one per seat, replacing the old one-off loop in main_ai_rounds with
+// something reusable and statistically comparable across strategy configs.
+pub struct Tournament {
+    pub rules: hearts::RuleSet,
+    pub strategies: Vec<Box<dyn CardChooser>>,
+    pub victory_points_fn: fn(&[i32]) -> Vec<u64>,
+    pub num_matches: u32,
+    pub base_seed: u64,
+}
+
+impl Tournament {
+    pub fn new(
+        rules: hearts::RuleSet,
+        strategies: Vec<Box<dyn CardChooser>>,
+        num_matches: u32,
+        base_seed: u64,
+    ) -> Tournament {
+        assert_eq!(strategies.len(), rules.num_players);
+        return Tournament {
+            rules: rules,
+            strategies: strategies,
+            victory_points_fn: default_victory_points,
+            num_matches: num_matches,
+            base_seed: base_seed,
+        };
+    }
+
+    // Plays every match to `self.rules.point_limit`. Match `i` is seeded
+    // from `self.base_seed + i`, so a run (and comparisons between runs
+    // after tweaking a strategy) is reproducible. If `on_match` is given, it
+    // is called after each match with the match index and that match's
+    // final scores and awarded victory points, so a caller can print
+    // progress; pass `None` to run headless.
+    pub fn run(
+        &self,
+        mut on_match: Option<&mut dyn FnMut(u32, &[i32], &[u64])>,
+    ) -> TournamentStats {
+        let num_players = self.rules.num_players;
+        let mut final_scores: Vec<Vec<i32>> = vec![Vec::new(); num_players];
+        let mut victory_points: Vec<Vec<u64>> = vec![Vec::new(); num_players];
+        let mut wins: Vec<u32> = vec![0; num_players];
+        let mut moon_shots: Vec<u32> = vec![0; num_players];
+        let mut num_rounds: u32 = 0;
+
+        for match_index in 0..self.num_matches {
+            let mut rng: StdRng = SeedableRng::seed_from_u64(self.base_seed + (match_index as u64));
+            let (mut deck, _removed) = Deck::for_players(num_players);
+            deck.shuffle(&mut rng);
+            let mut m = hearts::Match::deal_first_round(&self.rules, &deck);
+            loop {
+                if m.phase == hearts::MatchPhase::AwaitingPass {
+                    for p in 0..num_players {
+                        let pass_req = CardsToPassRequest {
+                            rules: self.rules.clone(),
+                            scores_before_round: m.scores.clone(),
+                            hand: m.round.players[p].hand.clone(),
+                            direction: m.round.pass_direction,
+                            num_cards: 3,
+                        };
+                        let cards = hearts_ai::choose_cards_to_pass(&pass_req);
+                        m.round.set_passed_cards_for_player(p, &cards);
+                    }
+                    m.pass_cards();
+                }
+                while m.phase == hearts::MatchPhase::Playing {
+                    let strategy = self.strategies[m.round.current_player_index()].as_ref();
+                    let card = hearts_ai::choose_card(&m.round, strategy, &mut rng);
+                    m.play_card(&card).expect("AI chose an illegal card");
+                }
+                num_rounds += 1;
+                if let Some(shooter) = hearts::moon_shooter_for_tricks(&m.round.prev_tricks, &self.rules) {
+                    moon_shots[shooter] += 1;
+                }
+                if m.phase == hearts::MatchPhase::MatchComplete {
+                    break;
+                }
+                deck.shuffle(&mut rng);
+                m.start_next_round(&deck);
+            }
+            let vp = (self.victory_points_fn)(&m.scores);
+            for p in 0..num_players {
+                final_scores[p].push(m.scores[p]);
+                victory_points[p].push(vp[p]);
+            }
+            for &w in m.winners().iter() {
+                wins[w] += 1;
+            }
+            if let Some(callback) = on_match.as_deref_mut() {
+                callback(match_index, &m.scores, &vp);
+            }
+        }
+
+        return TournamentStats {
+            num_matches: self.num_matches,
+            final_score_stddev: final_scores.iter().map(|v| stddev(v)).collect(),
+            mean_final_score: final_scores.iter().map(|v| mean(v)).collect(),
+            mean_victory_points: victory_points
+                .iter()
+                .map(|v| mean_u64(v))
+                .collect(),
+            win_rate: wins
+                .iter()
+                .map(|&w| (w as f64) / (self.num_matches as f64))
+                .collect(),
+            win_rate_stderr: wins
+                .iter()
+                .map(|&w| win_rate_stderr(w, self.num_matches))
+                .collect(),
+            moon_shot_rate: moon_shots
+                .iter()
+                .map(|&shots| (shots as f64) / (num_rounds as f64))
+                .collect(),
+        };
+    }
+}
+
+fn mean(values: &[i32]) -> f64 {
+    return (values.iter().sum::<i32>() as f64) / (values.len() as f64);
+}
+
+fn mean_u64(values: &[u64]) -> f64 {
+    return (values.iter().sum::<u64>() as f64) / (values.len() as f64);
+}
+
+// Population standard deviation of a seat's final scores across all
+// matches, so a caller can tell a strategy with a tight score spread from
+// one that wins on average but swings wildly.
+fn stddev(values: &[i32]) -> f64 {
+    let m = mean(values);
+    let variance = values.iter().map(|&v| (v as f64 - m).powi(2)).sum::<f64>() / (values.len() as f64);
+    return variance.sqrt();
+}
+
+// Standard error of a win rate estimated from `wins` out of `num_matches`
+// trials, i.e. sqrt(p*(1-p)/n).
+fn win_rate_stderr(wins: u32, num_matches: u32) -> f64 {
+    let n = num_matches as f64;
+    let p = (wins as f64) / n;
+    return ((p * (1.0 - p)) / n).sqrt();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hearts_ai::MonteCarloParams;
+
+    #[test]
+    fn test_default_victory_points_outright_win_and_ties() {
+        assert_eq!(default_victory_points(&[10, 20, 30, 40]), vec![12, 0, 0, 0]);
+        assert_eq!(default_victory_points(&[10, 10, 30, 40]), vec![6, 6, 0, 0]);
+        assert_eq!(default_victory_points(&[10, 10, 10, 40]), vec![4, 4, 4, 0]);
+        assert_eq!(default_victory_points(&[10, 10, 10, 10]), vec![3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_win_rate_stderr_is_zero_at_the_extremes() {
+        assert_eq!(win_rate_stderr(0, 10), 0.0);
+        assert_eq!(win_rate_stderr(10, 10), 0.0);
+        assert!(win_rate_stderr(5, 10) > 0.0);
+    }
+
+    #[test]
+    fn test_stddev_is_zero_for_identical_scores() {
+        assert_eq!(stddev(&[50, 50, 50]), 0.0);
+        assert!(stddev(&[10, 20, 30]) > 0.0);
+    }
+
+    #[test]
+    fn test_tournament_run_reports_stats_for_every_seat() {
+        let rules = hearts::RuleSet::default();
+        let strategies: Vec<Box<dyn CardChooser>> = vec![
+            Box::new(hearts_ai::AvoidPointsChooser),
+            Box::new(hearts_ai::RandomChooser),
+            Box::new(hearts_ai::MonteCarloChooser {
+                params: MonteCarloParams {
+                    num_hands: 5,
+                    rollouts_per_hand: 2,
+                },
+                rollout_chooser: Box::new(hearts_ai::RandomChooser),
+            }),
+            Box::new(hearts_ai::RandomChooser),
+        ];
+        let t = Tournament::new(rules.clone(), strategies, 4, 1234);
+        let stats = t.run(None);
+        assert_eq!(stats.num_matches, 4);
+        assert_eq!(stats.mean_final_score.len(), rules.num_players);
+        assert_eq!(stats.final_score_stddev.len(), rules.num_players);
+        assert_eq!(stats.mean_victory_points.len(), rules.num_players);
+        assert_eq!(stats.win_rate.len(), rules.num_players);
+        assert_eq!(stats.win_rate_stderr.len(), rules.num_players);
+        assert_eq!(stats.moon_shot_rate.len(), rules.num_players);
+        for &rate in stats.moon_shot_rate.iter() {
+            assert!(rate >= 0.0 && rate <= 1.0);
+        }
+        for &dev in stats.final_score_stddev.iter() {
+            assert!(dev >= 0.0);
+        }
+        let total_victory_points: f64 =
+            stats.mean_victory_points.iter().sum::<f64>() * (stats.num_matches as f64);
+        assert_eq!(total_victory_points.round() as u64, 12 * (stats.num_matches as u64));
+    }
+
+    #[test]
+    fn test_tournament_run_invokes_on_match_callback_per_match() {
+        let rules = hearts::RuleSet::default();
+        let strategies: Vec<Box<dyn CardChooser>> = (0..rules.num_players)
+            .map(|_| -> Box<dyn CardChooser> { Box::new(hearts_ai::RandomChooser) })
+            .collect();
+        let t = Tournament::new(rules, strategies, 3, 7);
+        let mut calls: Vec<u32> = Vec::new();
+        t.run(Some(&mut |match_index, _scores, _victory_points| {
+            calls.push(match_index);
+        }));
+        assert_eq!(calls, vec![0, 1, 2]);
+    }
+}